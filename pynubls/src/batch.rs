@@ -0,0 +1,68 @@
+use crate::bls::{InvalidSignature, Signature};
+use crate::keys::PublicKey;
+use crate::utils::g2_from_bytes;
+use nubls::{BatchVerifier as BatchVerifierStub, VerificationResult};
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::PyErr;
+
+#[pyclass]
+pub struct BatchVerifier {
+    inner: BatchVerifierStub,
+}
+
+impl Default for BatchVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[pymethods]
+impl BatchVerifier {
+    #[new]
+    pub fn new() -> Self {
+        BatchVerifier {
+            inner: BatchVerifierStub::new(),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        public_key: &PublicKey,
+        message: &PyBytes,
+        signature: &Signature,
+    ) -> PyResult<()> {
+        let msg = g2_from_bytes(message.as_bytes())?;
+        self.inner.add(
+            &public_key.inner,
+            &msg,
+            &signature.inner,
+        );
+        Ok(())
+    }
+
+    pub fn add_message(
+        &mut self,
+        public_key: &PublicKey,
+        message: &PyBytes,
+        signature: &Signature,
+    ) -> PyResult<()> {
+        self.inner
+            .add_message(&public_key.inner, message.as_bytes(), &signature.inner);
+        Ok(())
+    }
+
+    pub fn verify(&self) -> PyResult<bool> {
+        match self.inner.verify() {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidSignature, _>("Batch is not valid!"))
+            }
+        }
+    }
+
+    pub fn find_invalid(&self) -> PyResult<Vec<usize>> {
+        Ok(self.inner.find_invalid())
+    }
+}
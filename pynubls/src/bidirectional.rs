@@ -0,0 +1,49 @@
+use crate::error::to_py_err;
+use crate::prs::ResigningKey;
+use nubls::BidirectionalResigningKey as BidirectionalResigningKeyStub;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
+
+#[pyclass]
+pub struct BidirectionalResigningKey {
+    pub(crate) inner: BidirectionalResigningKeyStub,
+}
+
+#[pymethods]
+impl BidirectionalResigningKey {
+    #[new]
+    pub fn new(
+        alice_share: &ResigningKey,
+        bob_share: &ResigningKey,
+    ) -> PyResult<BidirectionalResigningKey> {
+        Ok(BidirectionalResigningKey {
+            inner: BidirectionalResigningKeyStub::new(&alice_share.inner, &bob_share.inner)
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_alice(&self) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self.inner.to_alice(),
+        })
+    }
+
+    pub fn to_bob(&self) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self.inner.to_bob(),
+        })
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<BidirectionalResigningKey> {
+        Ok(BidirectionalResigningKey {
+            inner: BidirectionalResigningKeyStub::from_bytes(bytes.as_bytes())
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
@@ -1,9 +1,16 @@
-use nubls::{Signature as SignatureStub, ThresholdSignature};
+use crate::error::to_py_err;
+use crate::keys::PublicKey;
+use crate::utils::g2_from_bytes;
+use bls12_381::G2Affine;
+use nubls::{
+    PublicKey as PublicKeyStub, Signature as SignatureStub, ThresholdSignature, VerificationResult,
+};
 
 use pyo3::create_exception;
 use pyo3::exceptions::PyException;
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyType};
+use pyo3::PyErr;
 
 create_exception!(nubls_wrapper, InvalidSignature, PyException);
 
@@ -21,30 +28,108 @@ impl Signature {
             .map(|fragment| fragment.inner)
             .collect();
         Ok(Signature {
-            inner: SignatureStub::assemble(&f[..]),
+            inner: SignatureStub::assemble(&f[..]).map_err(to_py_err)?,
         })
     }
 
+    #[classmethod]
+    pub fn assemble_robust(
+        _cls: &PyType,
+        fragments: Vec<PyRef<Signature>>,
+        threshold: usize,
+        public_key: &PublicKey,
+        message: &PyBytes,
+        fragment_keys: Option<Vec<PyRef<PublicKey>>>,
+    ) -> PyResult<(Signature, Vec<usize>)> {
+        let f: Vec<SignatureStub> = fragments
+            .into_iter()
+            .map(|fragment| fragment.inner)
+            .collect();
+        let k: Option<Vec<PublicKeyStub>> = fragment_keys.map(|keys| {
+            keys.into_iter()
+                .map(|fragment_key| fragment_key.inner)
+                .collect()
+        });
+        let msg = g2_from_bytes(message.as_bytes())?;
+        let (signature, faulty) = SignatureStub::assemble_robust(
+            &f[..],
+            threshold,
+            &public_key.inner,
+            &msg,
+            k.as_deref(),
+        )
+        .map_err(to_py_err)?;
+        Ok((Signature { inner: signature }, faulty))
+    }
+
+    #[classmethod]
+    pub fn aggregate(_cls: &PyType, signatures: Vec<PyRef<Signature>>) -> PyResult<Signature> {
+        let s: Vec<SignatureStub> = signatures
+            .into_iter()
+            .map(|signature| signature.inner)
+            .collect();
+        Ok(Signature {
+            inner: SignatureStub::aggregate(&s[..]),
+        })
+    }
+
+    pub fn aggregate_verify(
+        &self,
+        public_keys: Vec<PyRef<PublicKey>>,
+        messages: Vec<&PyBytes>,
+    ) -> PyResult<bool> {
+        let k: Vec<PublicKeyStub> = public_keys
+            .into_iter()
+            .map(|public_key| public_key.inner)
+            .collect();
+        let m: Vec<G2Affine> = messages
+            .into_iter()
+            .map(|message| g2_from_bytes(message.as_bytes()))
+            .collect::<PyResult<_>>()?;
+        match self.inner.aggregate_verify(&k[..], &m[..]) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidSignature, _>("Signature is not valid!"))
+            }
+        }
+    }
+
+    pub fn fast_aggregate_verify(
+        &self,
+        public_keys: Vec<PyRef<PublicKey>>,
+        message: &PyBytes,
+    ) -> PyResult<bool> {
+        let k: Vec<PublicKeyStub> = public_keys
+            .into_iter()
+            .map(|public_key| public_key.inner)
+            .collect();
+        let msg = g2_from_bytes(message.as_bytes())?;
+        match self.inner.fast_aggregate_verify(&k[..], &msg) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidSignature, _>("Signature is not valid!"))
+            }
+        }
+    }
+
     pub fn is_fragment(&self) -> PyResult<bool> {
         Ok(self.inner.is_fragment())
     }
 
+    pub fn fragment_id(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.fragment_id())
+    }
+
     #[classmethod]
     pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<Signature> {
         Ok(Signature {
-            inner: SignatureStub::from_bytes(&bytes.as_bytes()[..]),
+            inner: SignatureStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
         })
     }
 
     pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
-        if !self.inner.is_fragment() {
-            let mut buff = [0u8; 96];
-            self.inner.to_bytes(&mut buff);
-            Ok(&PyBytes::new(py, &buff))
-        } else {
-            let mut buff = [0u8; 128];
-            self.inner.to_bytes(&mut buff);
-            Ok(&PyBytes::new(py, &buff))
-        }
+        let mut buff = vec![0u8; self.inner.serialized_size()];
+        self.inner.to_bytes(&mut buff).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buff))
     }
 }
@@ -0,0 +1,73 @@
+use crate::bls::Signature;
+use crate::error::to_py_err;
+use crate::keys::PublicKey;
+use crate::prs::ResigningKey;
+use crate::utils::g2_from_bytes;
+use nubls::{ResigningChain as ResigningChainStub, ResigningKey as ResigningKeyStub};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
+
+#[pyclass]
+pub struct ResigningChain {
+    pub(crate) inner: ResigningChainStub,
+}
+
+#[pymethods]
+impl ResigningChain {
+    #[new]
+    pub fn new(hops: Vec<PyRef<ResigningKey>>) -> PyResult<ResigningChain> {
+        let h: Vec<ResigningKeyStub> = hops.into_iter().map(|hop| hop.inner).collect();
+        Ok(ResigningChain {
+            inner: ResigningChainStub::new(h).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn hops(&self) -> PyResult<Vec<ResigningKey>> {
+        Ok(self
+            .inner
+            .hops()
+            .iter()
+            .map(|hop| ResigningKey { inner: *hop })
+            .collect())
+    }
+
+    pub fn resigning_key(&self) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self.inner.resigning_key(),
+        })
+    }
+
+    pub fn resign(&self, signature: &Signature) -> PyResult<Signature> {
+        Ok(Signature {
+            inner: self.inner.resign(&signature.inner),
+        })
+    }
+
+    pub fn resign_checked(
+        &self,
+        designated_pubkey: &PublicKey,
+        message: &PyBytes,
+        signature: &Signature,
+    ) -> PyResult<Signature> {
+        let msg = g2_from_bytes(message.as_bytes())?;
+
+        Ok(Signature {
+            inner: self
+                .inner
+                .resign_checked(&designated_pubkey.inner, &msg, &signature.inner)
+                .map_err(to_py_err)?,
+        })
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<ResigningChain> {
+        Ok(ResigningChain {
+            inner: ResigningChainStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::bls::InvalidSignature;
+use nubls::Error;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::PyErr;
+
+create_exception!(nubls_wrapper, NuBLSError, PyValueError);
+create_exception!(nubls_wrapper, InvalidLength, NuBLSError);
+create_exception!(nubls_wrapper, NonCanonicalScalar, NuBLSError);
+create_exception!(nubls_wrapper, PointNotOnCurve, NuBLSError);
+create_exception!(nubls_wrapper, PointNotInSubgroup, NuBLSError);
+create_exception!(nubls_wrapper, IdentityPoint, NuBLSError);
+create_exception!(nubls_wrapper, InvalidFragmentIndex, NuBLSError);
+create_exception!(nubls_wrapper, DuplicateFragmentIndex, NuBLSError);
+create_exception!(nubls_wrapper, InvalidParticipant, NuBLSError);
+create_exception!(nubls_wrapper, WrongRound, NuBLSError);
+create_exception!(nubls_wrapper, DuplicateMessage, NuBLSError);
+create_exception!(nubls_wrapper, NotEnoughParticipants, NuBLSError);
+create_exception!(nubls_wrapper, NotEnoughFragments, NuBLSError);
+create_exception!(nubls_wrapper, InvalidRefreshShare, NuBLSError);
+create_exception!(nubls_wrapper, InvalidReshare, NuBLSError);
+create_exception!(nubls_wrapper, InvalidKeyType, NuBLSError);
+create_exception!(nubls_wrapper, InvalidMessageType, NuBLSError);
+create_exception!(nubls_wrapper, UnsupportedEpoch, NuBLSError);
+
+/// Maps each `nubls::Error` variant to its own Python exception, all of which
+/// subclass `NuBLSError` (itself a `ValueError`).
+pub(crate) fn to_py_err(err: Error) -> PyErr {
+    let msg = err.to_string();
+    match err {
+        Error::InvalidLength(_) => PyErr::new::<InvalidLength, _>(msg),
+        Error::NonCanonicalScalar => PyErr::new::<NonCanonicalScalar, _>(msg),
+        Error::PointNotOnCurve => PyErr::new::<PointNotOnCurve, _>(msg),
+        Error::PointNotInSubgroup => PyErr::new::<PointNotInSubgroup, _>(msg),
+        Error::IdentityPoint => PyErr::new::<IdentityPoint, _>(msg),
+        Error::InvalidFragmentIndex => PyErr::new::<InvalidFragmentIndex, _>(msg),
+        Error::DuplicateFragmentIndex => PyErr::new::<DuplicateFragmentIndex, _>(msg),
+        Error::InvalidParticipant(_) => PyErr::new::<InvalidParticipant, _>(msg),
+        Error::WrongRound => PyErr::new::<WrongRound, _>(msg),
+        Error::DuplicateMessage(_) => PyErr::new::<DuplicateMessage, _>(msg),
+        Error::NotEnoughParticipants => PyErr::new::<NotEnoughParticipants, _>(msg),
+        Error::NotEnoughFragments => PyErr::new::<NotEnoughFragments, _>(msg),
+        Error::InvalidRefreshShare(_) => PyErr::new::<InvalidRefreshShare, _>(msg),
+        Error::InvalidReshare(_) => PyErr::new::<InvalidReshare, _>(msg),
+        Error::InvalidSignature => PyErr::new::<InvalidSignature, _>(msg),
+        Error::InvalidKeyType(_) => PyErr::new::<InvalidKeyType, _>(msg),
+        Error::InvalidMessageType(_) => PyErr::new::<InvalidMessageType, _>(msg),
+        Error::UnsupportedEpoch => PyErr::new::<UnsupportedEpoch, _>(msg),
+    }
+}
+
+pub(crate) fn register(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("NuBLSError", py.get_type::<NuBLSError>())?;
+    m.add("InvalidLength", py.get_type::<InvalidLength>())?;
+    m.add("NonCanonicalScalar", py.get_type::<NonCanonicalScalar>())?;
+    m.add("PointNotOnCurve", py.get_type::<PointNotOnCurve>())?;
+    m.add("PointNotInSubgroup", py.get_type::<PointNotInSubgroup>())?;
+    m.add("IdentityPoint", py.get_type::<IdentityPoint>())?;
+    m.add("InvalidFragmentIndex", py.get_type::<InvalidFragmentIndex>())?;
+    m.add("DuplicateFragmentIndex", py.get_type::<DuplicateFragmentIndex>())?;
+    m.add("InvalidParticipant", py.get_type::<InvalidParticipant>())?;
+    m.add("WrongRound", py.get_type::<WrongRound>())?;
+    m.add("DuplicateMessage", py.get_type::<DuplicateMessage>())?;
+    m.add("NotEnoughParticipants", py.get_type::<NotEnoughParticipants>())?;
+    m.add("NotEnoughFragments", py.get_type::<NotEnoughFragments>())?;
+    m.add("InvalidRefreshShare", py.get_type::<InvalidRefreshShare>())?;
+    m.add("InvalidReshare", py.get_type::<InvalidReshare>())?;
+    m.add("InvalidKeyType", py.get_type::<InvalidKeyType>())?;
+    m.add("InvalidMessageType", py.get_type::<InvalidMessageType>())?;
+    m.add("UnsupportedEpoch", py.get_type::<UnsupportedEpoch>())?;
+    Ok(())
+}
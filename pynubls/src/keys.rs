@@ -1,21 +1,26 @@
 use crate::bls::{InvalidSignature, Signature};
-use bls12_381::G2Affine;
+use crate::error::to_py_err;
+use crate::prs::{DesignatedKey, ResigningKey};
+use crate::utils::g2_from_bytes;
+use crate::vss::{BlindingShare, FeldmanCommitments, InvalidFragment, PedersenCommitments};
 use nubls::{
-    PrivateKey as PrivateKeyStub, PublicKey as PublicKeyStub, PRSKey, ThresholdKey, VerificationResult,
+    DesignatedKeyKdf, DESIGNATED_KEY_DST, FeldmanCommitments as FeldmanCommitmentsStub, PRSKey, PrivateKey as PrivateKeyStub,
+    PublicKey as PublicKeyStub, ThresholdKey, VerificationResult,
 };
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyType};
 use pyo3::PyErr;
 
 #[pyclass]
 pub struct PublicKey {
-    inner: PublicKeyStub,
+    pub(crate) inner: PublicKeyStub,
 }
 
 #[pyclass]
 pub struct PrivateKey {
-    inner: PrivateKeyStub,
+    pub(crate) inner: PrivateKeyStub,
 }
 
 #[pymethods]
@@ -35,10 +40,21 @@ impl PrivateKey {
 
     // TODO: Finish implementation of `Signature`.
     pub fn sign(&self, message: &PyBytes) -> PyResult<Signature> {
-        let mut msg = [0u8; 96];
-        msg.copy_from_slice(message.as_bytes());
+        let msg = g2_from_bytes(message.as_bytes())?;
         Ok(Signature {
-            inner: self.inner.sign(&G2Affine::from_compressed(&msg).unwrap()),
+            inner: self.inner.sign(&msg),
+        })
+    }
+
+    pub fn sign_message(&self, message: &PyBytes) -> PyResult<Signature> {
+        Ok(Signature {
+            inner: self.inner.sign_message(message.as_bytes()),
+        })
+    }
+
+    pub fn prove_possession(&self) -> PyResult<Signature> {
+        Ok(Signature {
+            inner: self.inner.prove_possession(),
         })
     }
 
@@ -51,6 +67,106 @@ impl PrivateKey {
             .collect())
     }
 
+    pub fn split_with_ids(&self, m: usize, ids: Vec<u32>) -> PyResult<Vec<PrivateKey>> {
+        Ok(self
+            .inner
+            .split_with_ids(m, &ids[..])
+            .map_err(to_py_err)?
+            .into_iter()
+            .map(|fragment| PrivateKey { inner: fragment })
+            .collect())
+    }
+
+    pub fn split_verifiable(
+        &self,
+        m: usize,
+        n: usize,
+    ) -> PyResult<(Vec<PrivateKey>, FeldmanCommitments)> {
+        let (fragments, commitments) = self.inner.split_verifiable(m, n);
+        Ok((
+            fragments
+                .into_iter()
+                .map(|fragment| PrivateKey { inner: fragment })
+                .collect(),
+            FeldmanCommitments { inner: commitments },
+        ))
+    }
+
+    pub fn verify_fragment(&self, commitments: &FeldmanCommitments) -> PyResult<bool> {
+        match self.inner.verify_fragment(&commitments.inner) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidFragment, _>("Fragment is not valid!"))
+            }
+        }
+    }
+
+    pub fn split_pedersen(
+        &self,
+        m: usize,
+        n: usize,
+    ) -> PyResult<(Vec<PrivateKey>, Vec<BlindingShare>, PedersenCommitments)> {
+        let (fragments, blinding_shares, commitments) = self.inner.split_pedersen(m, n);
+        Ok((
+            fragments
+                .into_iter()
+                .map(|fragment| PrivateKey { inner: fragment })
+                .collect(),
+            blinding_shares
+                .into_iter()
+                .map(|blinding_share| BlindingShare {
+                    inner: blinding_share,
+                })
+                .collect(),
+            PedersenCommitments { inner: commitments },
+        ))
+    }
+
+    pub fn verify_pedersen_fragment(
+        &self,
+        blinding_share: &BlindingShare,
+        commitments: &PedersenCommitments,
+    ) -> PyResult<bool> {
+        match self
+            .inner
+            .verify_pedersen_fragment(&blinding_share.inner, &commitments.inner)
+        {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidFragment, _>("Fragment is not valid!"))
+            }
+        }
+    }
+
+    pub fn refresh(
+        &self,
+        m: usize,
+        dealings: Vec<(PyRef<PrivateKey>, PyRef<FeldmanCommitments>)>,
+    ) -> PyResult<PrivateKey> {
+        let d: Vec<(PrivateKeyStub, FeldmanCommitmentsStub)> = dealings
+            .into_iter()
+            .map(|(share, commitments)| (share.inner, commitments.inner.clone()))
+            .collect();
+        Ok(PrivateKey {
+            inner: self.inner.refresh(m, &d[..]).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn reshare(
+        &self,
+        m: usize,
+        ids: Vec<u32>,
+    ) -> PyResult<(Vec<PrivateKey>, FeldmanCommitments)> {
+        let (sub_shares, commitments) = self.inner.reshare(m, &ids[..]).map_err(to_py_err)?;
+        Ok((
+            sub_shares
+                .into_iter()
+                .map(|sub_share| PrivateKey { inner: sub_share })
+                .collect(),
+            FeldmanCommitments { inner: commitments },
+        ))
+    }
+
     #[classmethod]
     pub fn recover(_cls: &PyType, fragments: Vec<PyRef<PrivateKey>>) -> PyResult<PrivateKey> {
         let f: Vec<PrivateKeyStub> = fragments
@@ -58,7 +174,7 @@ impl PrivateKey {
             .map(|fragment| fragment.inner)
             .collect();
         Ok(PrivateKey {
-            inner: PrivateKeyStub::recover(&f[..]),
+            inner: PrivateKeyStub::recover(&f[..]).map_err(to_py_err)?,
         })
     }
 
@@ -66,40 +182,149 @@ impl PrivateKey {
         Ok(self.inner.is_fragment())
     }
 
+    pub fn fragment_id(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.fragment_id())
+    }
+
     #[classmethod]
     pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<PrivateKey> {
         Ok(PrivateKey {
-            inner: PrivateKeyStub::from_bytes(&bytes.as_bytes()[..]),
+            inner: PrivateKeyStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
         })
     }
 
     pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
-        if !self.inner.is_fragment() {
-            let mut buff = [0u8; 32];
-            self.inner.to_bytes(&mut buff);
-            Ok(&PyBytes::new(py, &buff))
-        } else {
-            let mut buff = [0u8; 64];
-            self.inner.to_bytes(&mut buff);
-            Ok(&PyBytes::new(py, &buff))
-        }
+        let mut buff = vec![0u8; self.inner.serialized_size()];
+        self.inner.to_bytes(&mut buff).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buff))
     }
 
-    pub fn resigning_key(&self, bob_pubkey: &PublicKey) -> PyResult<PrivateKey> {
-        Ok(PrivateKey {
-            inner: self.inner.resigning_key(&bob_pubkey.inner)
+    #[args("*", dst = "None", legacy = "None")]
+    pub fn resigning_key(
+        &self,
+        bob_pubkey: &PublicKey,
+        dst: Option<&PyBytes>,
+        legacy: Option<bool>,
+    ) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self
+                .inner
+                .resigning_key_with_kdf(&bob_pubkey.inner, designated_key_kdf(dst, legacy)?)
         })
     }
 
-    pub fn designated_key(&self, alice_pubkey: &PublicKey) -> PyResult<PrivateKey> {
-        Ok(PrivateKey {
-            inner: self.inner.designated_key(&alice_pubkey.inner)
+    pub fn split_resigning_key(
+        &self,
+        bob_pubkey: &PublicKey,
+        m: usize,
+        n: usize,
+    ) -> PyResult<Vec<ResigningKey>> {
+        Ok(self
+            .inner
+            .split_resigning_key(&bob_pubkey.inner, m, n)
+            .into_iter()
+            .map(|fragment| ResigningKey { inner: fragment })
+            .collect())
+    }
+
+    #[args("*", dst = "None", legacy = "None")]
+    pub fn designated_key(
+        &self,
+        alice_pubkey: &PublicKey,
+        dst: Option<&PyBytes>,
+        legacy: Option<bool>,
+    ) -> PyResult<DesignatedKey> {
+        Ok(DesignatedKey {
+            inner: self
+                .inner
+                .designated_key_with_kdf(&alice_pubkey.inner, designated_key_kdf(dst, legacy)?)
         })
     }
 
-    pub fn resign(&self, signature: &Signature) -> PyResult<Signature> {
-        Ok(Signature {
-            inner: self.inner.resign(&signature.inner)
+    #[args("*", dst = "None", legacy = "None")]
+    pub fn hop_resigning_key(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        dst: Option<&PyBytes>,
+        legacy: Option<bool>,
+    ) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self.inner.hop_resigning_key_with_kdf(
+                &from_pubkey.inner,
+                &to_pubkey.inner,
+                designated_key_kdf(dst, legacy)?,
+            ),
+        })
+    }
+
+    #[args("*", dst = "None")]
+    pub fn hop_resigning_key_for_epoch(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        from_epoch: &PyBytes,
+        to_epoch: &PyBytes,
+        dst: Option<&PyBytes>,
+    ) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self
+                .inner
+                .hop_resigning_key_for_epoch(
+                    &from_pubkey.inner,
+                    &to_pubkey.inner,
+                    from_epoch.as_bytes(),
+                    to_epoch.as_bytes(),
+                    epoch_key_kdf(dst),
+                )
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn bidirectional_resigning_share(
+        &self,
+        counterparty_pubkey: &PublicKey,
+    ) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self
+                .inner
+                .bidirectional_resigning_share(&counterparty_pubkey.inner),
+        })
+    }
+
+    #[args("*", dst = "None")]
+    pub fn resigning_key_for_epoch(
+        &self,
+        bob_pubkey: &PublicKey,
+        epoch: &PyBytes,
+        dst: Option<&PyBytes>,
+    ) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: self
+                .inner
+                .resigning_key_for_epoch(&bob_pubkey.inner, epoch.as_bytes(), epoch_key_kdf(dst))
+                .map_err(to_py_err)?,
+        })
+    }
+
+    #[args("*", dst = "None")]
+    pub fn designated_key_for_epoch(
+        &self,
+        alice_pubkey: &PublicKey,
+        epoch: &PyBytes,
+        dst: Option<&PyBytes>,
+    ) -> PyResult<DesignatedKey> {
+        Ok(DesignatedKey {
+            inner: self
+                .inner
+                .designated_key_for_epoch(&alice_pubkey.inner, epoch.as_bytes(), epoch_key_kdf(dst))
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn designated_public_key(&self, counterparty_pubkey: &PublicKey) -> PyResult<PublicKey> {
+        Ok(PublicKey {
+            inner: self.inner.designated_public_key(&counterparty_pubkey.inner),
         })
     }
 }
@@ -107,12 +332,9 @@ impl PrivateKey {
 #[pymethods]
 impl PublicKey {
     pub fn verify(&self, message: &PyBytes, signature: &Signature) -> PyResult<bool> {
-        let mut msg = [0u8; 96];
-        msg.copy_from_slice(message.as_bytes());
+        let msg = g2_from_bytes(message.as_bytes())?;
 
-        let res = self
-            .inner
-            .verify(&G2Affine::from_compressed(&msg).unwrap(), &signature.inner);
+        let res = self.inner.verify(&msg, &signature.inner);
         match res {
             VerificationResult::Valid => Ok(true),
             VerificationResult::Invalid => {
@@ -121,16 +343,131 @@ impl PublicKey {
         }
     }
 
+    pub fn verify_message(&self, message: &PyBytes, signature: &Signature) -> PyResult<bool> {
+        match self.inner.verify_message(message.as_bytes(), &signature.inner) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidSignature, _>("Signature is not valid!"))
+            }
+        }
+    }
+
+    pub fn verify_possession(&self, proof: &Signature) -> PyResult<bool> {
+        match self.inner.verify_possession(&proof.inner) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => Err(PyErr::new::<InvalidSignature, _>(
+                "Proof of possession is not valid!",
+            )),
+        }
+    }
+
+    pub fn verify_fragment(&self, message: &PyBytes, signature: &Signature) -> PyResult<bool> {
+        let msg = g2_from_bytes(message.as_bytes())?;
+
+        let res = self.inner.verify_fragment(&msg, &signature.inner);
+        match res {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => Err(PyErr::new::<InvalidSignature, _>(
+                "Signature fragment is not valid!",
+            )),
+        }
+    }
+
+    pub fn verify_fragment_message(
+        &self,
+        message: &PyBytes,
+        signature: &Signature,
+    ) -> PyResult<bool> {
+        match self
+            .inner
+            .verify_fragment_message(message.as_bytes(), &signature.inner)
+        {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => Err(PyErr::new::<InvalidSignature, _>(
+                "Signature fragment is not valid!",
+            )),
+        }
+    }
+
+    pub fn is_fragment(&self) -> PyResult<bool> {
+        Ok(self.inner.is_fragment())
+    }
+
+    pub fn fragment_id(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.fragment_id())
+    }
+
+    pub fn refresh(
+        &self,
+        m: usize,
+        commitments: Vec<PyRef<FeldmanCommitments>>,
+    ) -> PyResult<PublicKey> {
+        let c: Vec<FeldmanCommitmentsStub> = commitments
+            .into_iter()
+            .map(|commitments| commitments.inner.clone())
+            .collect();
+        Ok(PublicKey {
+            inner: self.inner.refresh(m, &c[..]).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn designated_public_key(&self, resigning_key: &ResigningKey) -> PyResult<PublicKey> {
+        Ok(PublicKey {
+            inner: self
+                .inner
+                .designated_public_key(&resigning_key.inner)
+                .map_err(to_py_err)?,
+        })
+    }
+
+    #[classmethod]
+    pub fn aggregate(_cls: &PyType, public_keys: Vec<PyRef<PublicKey>>) -> PyResult<PublicKey> {
+        let k: Vec<PublicKeyStub> = public_keys
+            .into_iter()
+            .map(|public_key| public_key.inner)
+            .collect();
+        Ok(PublicKey {
+            inner: PublicKeyStub::aggregate(&k[..]),
+        })
+    }
+
     #[classmethod]
     pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<PublicKey> {
-        let mut key = [0u8; 48];
-        key.copy_from_slice(bytes.as_bytes());
         Ok(PublicKey {
-            inner: PublicKeyStub::from_bytes(&key),
+            inner: PublicKeyStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
         })
     }
 
     pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
-        Ok(&PyBytes::new(py, &self.inner.to_bytes()[..]))
+        let mut buff = vec![0u8; self.inner.serialized_size()];
+        self.inner.to_bytes(&mut buff).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buff))
     }
 }
+
+/// Selects the `DesignatedKeyKdf` for the keyword-only `dst` and `legacy`
+/// arguments: HKDF under the `dst`, or under the default DST with
+/// `legacy=False`, and otherwise the legacy derivation of older versions.
+///
+/// A `dst` only applies to HKDF, so `legacy=True` with a `dst` raises a
+/// `ValueError` rather than ignoring the `dst`.
+fn designated_key_kdf(
+    dst: Option<&PyBytes>,
+    legacy: Option<bool>,
+) -> PyResult<DesignatedKeyKdf<'_>> {
+    match (legacy, dst) {
+        (Some(true), Some(_)) => Err(PyValueError::new_err(
+            "A DST can't be used with the legacy derivation",
+        )),
+        (Some(false), None) => Ok(DesignatedKeyKdf::HkdfSha256(DESIGNATED_KEY_DST)),
+        (_, Some(dst)) => Ok(DesignatedKeyKdf::HkdfSha256(dst.as_bytes())),
+        (_, None) => Ok(DesignatedKeyKdf::Legacy),
+    }
+}
+
+/// Selects the `DesignatedKeyKdf` for keys scoped to an epoch: HKDF under the
+/// keyword-only `dst`, or under the default DST. Only HKDF binds the epoch, so
+/// there is no legacy option.
+fn epoch_key_kdf(dst: Option<&PyBytes>) -> DesignatedKeyKdf<'_> {
+    DesignatedKeyKdf::HkdfSha256(dst.map_or(DESIGNATED_KEY_DST, |dst| dst.as_bytes()))
+}
@@ -2,15 +2,106 @@ extern crate nubls;
 
 use crate::bls::InvalidSignature;
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::wrap_pyfunction;
 
+pub mod batch;
+pub mod bidirectional;
 pub mod bls;
+pub mod chain;
+pub mod error;
 pub mod keys;
+pub mod prs;
+mod utils;
+pub mod vss;
+
+/// Hashes a message to G_2 per the IETF hash_to_curve specification, and
+/// returns the compressed point for use with `PrivateKey.sign`/`PublicKey.verify`.
+///
+/// When no `dst` is given, this uses the empty DST, like the `py_ecc` helper
+/// that `hash_message` replaced, so callers relying on the default keep the
+/// same DST. Pass the DST of a ciphersuite to match `PrivateKey.sign_message`.
+#[pyfunction]
+fn hash_message<'p>(
+    py: Python<'p>,
+    message: &PyBytes,
+    dst: Option<&PyBytes>,
+) -> PyResult<&'p PyBytes> {
+    let dst = dst.map_or(&b""[..], |dst| dst.as_bytes());
+    let point = nubls::hash_to_g2(message.as_bytes(), dst);
+    Ok(PyBytes::new(py, &point.to_compressed()[..]))
+}
+
+/// Deals one fragment holder's contribution to a refresh round of a threshold
+/// key with a threshold of `m`, held by the fragment holders with `ids`.
+///
+/// Returns the share of zero for each holder, and the commitments to publish.
+#[pyfunction]
+fn refresh_dealing(
+    m: usize,
+    ids: Vec<u32>,
+) -> PyResult<(Vec<keys::PrivateKey>, vss::FeldmanCommitments)> {
+    let (shares, commitments) = nubls::refresh_dealing(m, &ids[..]).map_err(error::to_py_err)?;
+    Ok((
+        shares
+            .into_iter()
+            .map(|share| keys::PrivateKey { inner: share })
+            .collect(),
+        vss::FeldmanCommitments { inner: commitments },
+    ))
+}
+
+/// Combines the sub-shares a new fragment holder received during resharing
+/// into its fragment of the key with the group `public_key`, with a new
+/// threshold of `m`.
+///
+/// Each dealing is the old fragment's `PublicKey`, the sub-share for this
+/// holder, and the dealer's commitments. Returns the new fragment, and the
+/// commitments of the new sharing.
+#[pyfunction]
+fn combine_reshares(
+    m: usize,
+    public_key: &keys::PublicKey,
+    dealings: Vec<(
+        PyRef<keys::PublicKey>,
+        PyRef<keys::PrivateKey>,
+        PyRef<vss::FeldmanCommitments>,
+    )>,
+) -> PyResult<(keys::PrivateKey, vss::FeldmanCommitments)> {
+    let d: Vec<(nubls::PublicKey, nubls::PrivateKey, nubls::FeldmanCommitments)> = dealings
+        .into_iter()
+        .map(|(dealer, sub_share, commitments)| {
+            (dealer.inner, sub_share.inner, commitments.inner.clone())
+        })
+        .collect();
+    let (fragment, commitments) =
+        nubls::combine_reshares(m, &public_key.inner, &d[..]).map_err(error::to_py_err)?;
+    Ok((
+        keys::PrivateKey { inner: fragment },
+        vss::FeldmanCommitments { inner: commitments },
+    ))
+}
 
 #[pymodule]
 fn pynubls(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<keys::PrivateKey>()?;
     m.add_class::<keys::PublicKey>()?;
+    m.add_class::<prs::ResigningKey>()?;
+    m.add_class::<prs::DesignatedKey>()?;
+    m.add_class::<prs::ResigningKeyProof>()?;
     m.add_class::<bls::Signature>()?;
+    m.add_class::<chain::ResigningChain>()?;
+    m.add_class::<bidirectional::BidirectionalResigningKey>()?;
+    m.add_class::<batch::BatchVerifier>()?;
+    m.add_class::<vss::FeldmanCommitments>()?;
+    m.add_class::<vss::PedersenCommitments>()?;
+    m.add_class::<vss::BlindingShare>()?;
+    m.add_function(wrap_pyfunction!(hash_message, m)?)?;
+    m.add_function(wrap_pyfunction!(refresh_dealing, m)?)?;
+    m.add_function(wrap_pyfunction!(combine_reshares, m)?)?;
     m.add("InvalidSignature", py.get_type::<InvalidSignature>())?;
+    m.add("InvalidFragment", py.get_type::<vss::InvalidFragment>())?;
+    m.add("InvalidProof", py.get_type::<prs::InvalidProof>())?;
+    error::register(py, m)?;
     Ok(())
 }
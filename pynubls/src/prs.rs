@@ -0,0 +1,196 @@
+use crate::bls::Signature;
+use crate::error::to_py_err;
+use crate::keys::PublicKey;
+use crate::utils::g2_from_bytes;
+use nubls::{
+    DesignatedKey as DesignatedKeyStub, ResigningKey as ResigningKeyStub,
+    ResigningKeyProof as ResigningKeyProofStub, ThresholdKey, VerificationResult,
+};
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
+
+create_exception!(nubls_wrapper, InvalidProof, PyException);
+
+#[pyclass]
+pub struct DesignatedKey {
+    pub(crate) inner: DesignatedKeyStub,
+}
+
+#[pymethods]
+impl DesignatedKey {
+    pub fn public_key(&self) -> PyResult<PublicKey> {
+        Ok(PublicKey {
+            inner: self.inner.public_key(),
+        })
+    }
+
+    pub fn sign(&self, message: &PyBytes) -> PyResult<Signature> {
+        let msg = g2_from_bytes(message.as_bytes())?;
+
+        Ok(Signature {
+            inner: self.inner.sign(&msg),
+        })
+    }
+
+    pub fn sign_message(&self, message: &PyBytes) -> PyResult<Signature> {
+        Ok(Signature {
+            inner: self.inner.sign_message(message.as_bytes()),
+        })
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<DesignatedKey> {
+        Ok(DesignatedKey {
+            inner: DesignatedKeyStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        let mut buff = vec![0u8; self.inner.serialized_size()];
+        self.inner.to_bytes(&mut buff).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buff))
+    }
+}
+
+#[pyclass]
+pub struct ResigningKey {
+    pub(crate) inner: ResigningKeyStub,
+}
+
+#[pymethods]
+impl ResigningKey {
+    pub fn resign(&self, signature: &Signature) -> PyResult<Signature> {
+        Ok(Signature {
+            inner: self.inner.resign(&signature.inner),
+        })
+    }
+
+    pub fn resign_checked(
+        &self,
+        designated_pubkey: &PublicKey,
+        message: &PyBytes,
+        signature: &Signature,
+    ) -> PyResult<Signature> {
+        let msg = g2_from_bytes(message.as_bytes())?;
+
+        Ok(Signature {
+            inner: self
+                .inner
+                .resign_checked(&designated_pubkey.inner, &msg, &signature.inner)
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn public_key(&self) -> PyResult<PublicKey> {
+        Ok(PublicKey {
+            inner: self.inner.public_key(),
+        })
+    }
+
+    pub fn prove_well_formed(&self, designated_pubkey: &PublicKey) -> PyResult<ResigningKeyProof> {
+        Ok(ResigningKeyProof {
+            inner: self
+                .inner
+                .prove_well_formed(&designated_pubkey.inner)
+                .map_err(to_py_err)?,
+        })
+    }
+
+    pub fn verify_proof(
+        &self,
+        proof: &ResigningKeyProof,
+        designated_pubkey: &PublicKey,
+        alice_pubkey: &PublicKey,
+    ) -> PyResult<bool> {
+        match self
+            .inner
+            .verify_proof(&proof.inner, &designated_pubkey.inner, &alice_pubkey.inner)
+        {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidProof, _>("Proof is not valid!"))
+            }
+        }
+    }
+
+    pub fn split(&self, m: usize, n: usize) -> PyResult<Vec<ResigningKey>> {
+        Ok(self
+            .inner
+            .split(m, n)
+            .into_iter()
+            .map(|fragment| ResigningKey { inner: fragment })
+            .collect())
+    }
+
+    #[classmethod]
+    pub fn recover(_cls: &PyType, fragments: Vec<PyRef<ResigningKey>>) -> PyResult<ResigningKey> {
+        let f: Vec<ResigningKeyStub> = fragments
+            .into_iter()
+            .map(|fragment| fragment.inner)
+            .collect();
+        Ok(ResigningKey {
+            inner: ResigningKeyStub::recover(&f[..]).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn is_fragment(&self) -> PyResult<bool> {
+        Ok(self.inner.is_fragment())
+    }
+
+    pub fn fragment_id(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.fragment_id())
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<ResigningKey> {
+        Ok(ResigningKey {
+            inner: ResigningKeyStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        let mut buff = vec![0u8; self.inner.serialized_size()];
+        self.inner.to_bytes(&mut buff).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buff))
+    }
+}
+
+#[pyclass]
+pub struct ResigningKeyProof {
+    pub(crate) inner: ResigningKeyProofStub,
+}
+
+#[pymethods]
+impl ResigningKeyProof {
+    pub fn verify(
+        &self,
+        resigning_pubkey: &PublicKey,
+        designated_pubkey: &PublicKey,
+        alice_pubkey: &PublicKey,
+    ) -> PyResult<bool> {
+        match self.inner.verify(
+            &resigning_pubkey.inner,
+            &designated_pubkey.inner,
+            &alice_pubkey.inner,
+        ) {
+            VerificationResult::Valid => Ok(true),
+            VerificationResult::Invalid => {
+                Err(PyErr::new::<InvalidProof, _>("Proof is not valid!"))
+            }
+        }
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<ResigningKeyProof> {
+        Ok(ResigningKeyProof {
+            inner: ResigningKeyProofStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
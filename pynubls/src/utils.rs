@@ -0,0 +1,18 @@
+use crate::error::to_py_err;
+use bls12_381::G2Affine;
+use nubls::Error;
+use pyo3::prelude::*;
+
+/// Deserializes a message given as a compressed point in G_2, e.g. from
+/// `hash_message`, raising `InvalidLength` or `PointNotOnCurve` rather than
+/// panicking on malformed bytes.
+pub(crate) fn g2_from_bytes(bytes: &[u8]) -> PyResult<G2Affine> {
+    if bytes.len() != 96 {
+        return Err(to_py_err(Error::InvalidLength(bytes.len())));
+    }
+    let mut point_bytes = [0u8; 96];
+    point_bytes.copy_from_slice(bytes);
+
+    Option::from(G2Affine::from_compressed(&point_bytes))
+        .ok_or_else(|| to_py_err(Error::PointNotOnCurve))
+}
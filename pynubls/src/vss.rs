@@ -0,0 +1,84 @@
+use crate::error::to_py_err;
+use crate::keys::PublicKey;
+use nubls::{
+    BlindingShare as BlindingShareStub, FeldmanCommitments as FeldmanCommitmentsStub,
+    PedersenCommitments as PedersenCommitmentsStub,
+};
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
+
+create_exception!(nubls_wrapper, InvalidFragment, PyException);
+
+#[pyclass]
+pub struct FeldmanCommitments {
+    pub(crate) inner: FeldmanCommitmentsStub,
+}
+
+#[pymethods]
+impl FeldmanCommitments {
+    pub fn threshold(&self) -> PyResult<usize> {
+        Ok(self.inner.threshold())
+    }
+
+    pub fn public_key(&self) -> PyResult<PublicKey> {
+        Ok(PublicKey {
+            inner: self.inner.public_key(),
+        })
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<FeldmanCommitments> {
+        Ok(FeldmanCommitments {
+            inner: FeldmanCommitmentsStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
+
+#[pyclass]
+pub struct PedersenCommitments {
+    pub(crate) inner: PedersenCommitmentsStub,
+}
+
+#[pymethods]
+impl PedersenCommitments {
+    pub fn threshold(&self) -> PyResult<usize> {
+        Ok(self.inner.threshold())
+    }
+
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<PedersenCommitments> {
+        Ok(PedersenCommitments {
+            inner: PedersenCommitmentsStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
+
+#[pyclass]
+pub struct BlindingShare {
+    pub(crate) inner: BlindingShareStub,
+}
+
+#[pymethods]
+impl BlindingShare {
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, bytes: &PyBytes) -> PyResult<BlindingShare> {
+        Ok(BlindingShare {
+            inner: BlindingShareStub::from_bytes(bytes.as_bytes()).map_err(to_py_err)?,
+        })
+    }
+
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.to_bytes()[..]))
+    }
+}
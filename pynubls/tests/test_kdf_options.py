@@ -0,0 +1,58 @@
+"""Tests for the keyword-only KDF options of the designated key methods."""
+import pytest
+
+from pynubls import PrivateKey
+
+
+def test_kdf_options():
+    alice_priv, bob_priv = PrivateKey.random(), PrivateKey.random()
+    alice_pub, bob_pub = alice_priv.public_key(), bob_priv.public_key()
+    message = b'Penumbral!'
+
+    for options in [{}, {'legacy': True}, {'legacy': False}, {'dst': b'MY_APP_PRS_'}]:
+        designated_key = bob_priv.designated_key(alice_pub, **options)
+        resigning_key = alice_priv.resigning_key(bob_pub, **options)
+        resigned_sig = resigning_key.resign(designated_key.sign_message(message))
+        assert resigned_sig.to_bytes() == alice_priv.sign_message(message).to_bytes()
+
+    # The default is the legacy derivation, and `legacy=False` is HKDF.
+    default_key = bob_priv.designated_key(alice_pub).to_bytes()
+    assert bob_priv.designated_key(alice_pub, legacy=True).to_bytes() == default_key
+    assert bob_priv.designated_key(alice_pub, legacy=False).to_bytes() != default_key
+
+
+def test_kdf_options_are_keyword_only():
+    alice_priv, bob_priv = PrivateKey.random(), PrivateKey.random()
+    with pytest.raises(TypeError):
+        bob_priv.designated_key(alice_priv.public_key(), None, True)
+    with pytest.raises(TypeError):
+        alice_priv.resigning_key(bob_priv.public_key(), b'MY_APP_PRS_')
+
+
+def test_legacy_with_dst_raises():
+    alice_priv, bob_priv = PrivateKey.random(), PrivateKey.random()
+    with pytest.raises(ValueError):
+        bob_priv.designated_key(alice_priv.public_key(), dst=b'MY_APP_PRS_', legacy=True)
+    with pytest.raises(ValueError):
+        alice_priv.resigning_key(bob_priv.public_key(), dst=b'MY_APP_PRS_', legacy=True)
+    with pytest.raises(ValueError):
+        bob_priv.hop_resigning_key(
+            alice_priv.public_key(), PrivateKey.random().public_key(),
+            dst=b'MY_APP_PRS_', legacy=True)
+
+
+def test_epoch_dst():
+    alice_priv, bob_priv = PrivateKey.random(), PrivateKey.random()
+    alice_pub, bob_pub = alice_priv.public_key(), bob_priv.public_key()
+    message = b'Penumbral!'
+
+    designated_key = bob_priv.designated_key_for_epoch(alice_pub, b'2026-10', dst=b'MY_APP_PRS_')
+    resigning_key = alice_priv.resigning_key_for_epoch(bob_pub, b'2026-10', dst=b'MY_APP_PRS_')
+    resigned_sig = resigning_key.resign(designated_key.sign_message(message))
+    assert resigned_sig.to_bytes() == alice_priv.sign_message(message).to_bytes()
+
+    # Epoch keys of different DSTs are apart.
+    default_key = bob_priv.designated_key_for_epoch(alice_pub, b'2026-10')
+    assert default_key.to_bytes() != designated_key.to_bytes()
+    with pytest.raises(TypeError):
+        bob_priv.designated_key_for_epoch(alice_pub, b'2026-10', b'MY_APP_PRS_')
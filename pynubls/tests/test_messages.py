@@ -0,0 +1,36 @@
+"""Tests that malformed G_2 messages raise rather than panic."""
+import pytest
+
+from pynubls import (PrivateKey, Signature, ResigningChain, BatchVerifier,
+                     InvalidLength, PointNotOnCurve, hash_message)
+
+
+def test_malformed_messages_raise():
+    alice_priv, bob_priv = PrivateKey.random(), PrivateKey.random()
+    alice_pub = alice_priv.public_key()
+    sig = alice_priv.sign(hash_message(b'NuBLS!'))
+    resigning_key = alice_priv.resigning_key(bob_priv.public_key())
+    designated_pub = bob_priv.designated_public_key(alice_pub)
+
+    # A compressed point with the flag bits cleared is never valid.
+    for message, error in [(b'short', InvalidLength), (bytes(96), PointNotOnCurve)]:
+        with pytest.raises(error):
+            alice_priv.sign(message)
+        with pytest.raises(error):
+            alice_pub.verify(message, sig)
+        with pytest.raises(error):
+            alice_pub.verify_fragment(message, sig)
+        with pytest.raises(error):
+            bob_priv.designated_key(alice_pub).sign(message)
+        with pytest.raises(error):
+            resigning_key.resign_checked(designated_pub, message, sig)
+        with pytest.raises(error):
+            ResigningChain([resigning_key]).resign_checked(designated_pub, message, sig)
+        with pytest.raises(error):
+            sig.fast_aggregate_verify([alice_pub], message)
+        with pytest.raises(error):
+            sig.aggregate_verify([alice_pub], [message])
+        with pytest.raises(error):
+            Signature.assemble_robust([sig], 1, alice_pub, message)
+        with pytest.raises(error):
+            BatchVerifier().add(alice_pub, message, sig)
@@ -0,0 +1,84 @@
+"""Known-answer tests for Penumbral, run against the vendored test vectors that
+rust-nubls also runs.
+
+Run with `pytest` once pynubls is installed, or directly with `python`.
+"""
+import os
+
+from pynubls import PrivateKey, PublicKey, Signature
+
+VECTORS_PATH = os.path.join(
+    os.path.dirname(os.path.abspath(__file__)), '..', '..', 'test-vectors', 'penumbral.txt')
+
+
+def load_vectors(path=VECTORS_PATH):
+    """Parses the test vectors into a dict of each vector's values by name."""
+    with open(path) as f:
+        blocks = f.read().split('\n\n')
+
+    vectors = []
+    for block in blocks:
+        vector = {}
+        for line in block.splitlines():
+            if line.startswith('#'):
+                continue
+            name, value = line.split('=', 1)
+            vector[name.strip()] = value.strip()
+        if vector:
+            vectors.append(vector)
+    return vectors
+
+
+def check_vector(vector):
+    h = bytes.fromhex
+
+    alice_priv = PrivateKey.from_bytes(h(vector['alice_sk']))
+    bob_priv = PrivateKey.from_bytes(h(vector['bob_sk']))
+    alice_pub, bob_pub = alice_priv.public_key(), bob_priv.public_key()
+    assert alice_pub.to_bytes() == h(vector['alice_pk'])
+    assert bob_pub.to_bytes() == h(vector['bob_pk'])
+
+    epoch = h(vector['epoch'])
+    if epoch:
+        dst = h(vector['dst'])
+        designated_key = bob_priv.designated_key_for_epoch(alice_pub, epoch, dst=dst)
+        resigning_key = alice_priv.resigning_key_for_epoch(bob_pub, epoch, dst=dst)
+    elif vector['kdf'] == 'legacy':
+        designated_key = bob_priv.designated_key(alice_pub, legacy=True)
+        resigning_key = alice_priv.resigning_key(bob_pub, legacy=True)
+    elif vector['kdf'] == 'hkdf-sha256':
+        dst = h(vector['dst'])
+        designated_key = bob_priv.designated_key(alice_pub, dst=dst)
+        resigning_key = alice_priv.resigning_key(bob_pub, dst=dst)
+    else:
+        raise ValueError("Unknown KDF in test vector: {}".format(vector['kdf']))
+
+    # Serialized keys start with their tag byte.
+    assert designated_key.to_bytes()[1:] == h(vector['designated_key'])
+    assert resigning_key.to_bytes()[1:] == h(vector['resigning_key'])
+
+    designated_pub = PublicKey.from_bytes(h(vector['designated_pk']))
+    assert designated_key.public_key().to_bytes() == designated_pub.to_bytes()
+    assert alice_pub.designated_public_key(resigning_key).to_bytes() == designated_pub.to_bytes()
+
+    message = h(vector['message'])
+    designated_sig = designated_key.sign_message(message)
+    assert designated_sig.to_bytes() == h(vector['designated_signature'])
+    assert designated_pub.verify_message(message, designated_sig)
+
+    resigned_sig = resigning_key.resign(Signature.from_bytes(h(vector['designated_signature'])))
+    assert resigned_sig.to_bytes() == h(vector['resigned_signature'])
+    assert resigned_sig.to_bytes() == alice_priv.sign_message(message).to_bytes()
+    assert alice_pub.verify_message(message, resigned_sig)
+
+
+def test_penumbral_vectors():
+    vectors = load_vectors()
+    assert len(vectors) == 4
+    for vector in vectors:
+        check_vector(vector)
+
+
+if __name__ == '__main__':
+    test_penumbral_vectors()
+    print("All Penumbral test vectors passed.")
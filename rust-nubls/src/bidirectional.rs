@@ -0,0 +1,196 @@
+use bls12_381::Scalar;
+
+use crate::error::Error;
+use crate::keys::SCALAR_BYTES_LENGTH;
+use crate::prs::ResigningKey;
+use crate::utils::scalar_from_bytes;
+
+/// A `BidirectionalResigningKey` re-signs signatures in either direction
+/// between Alice's and Bob's own keys, as an opt-in alternative to the
+/// unidirectional re-signing keys of Penumbral.
+///
+/// It's built by a proxy from Alice's and Bob's shares, from
+/// `PRSKey::bidirectional_resigning_share`. Alice's share re-signs from a
+/// shared designated key $\phi$ to her key, and Bob's to his, so the
+/// bidirectional key is $a \cdot \phi^{-1} \cdot (b \cdot \phi^{-1})^{-1} = a / b$.
+/// $\phi$ is derived under its own domain separation tag, so neither share
+/// re-signs signatures under a Penumbral designated key.
+///
+/// Note: The key $a / b$ links Alice's and Bob's long-term keys themselves.
+/// Whoever holds it can turn *any* signature made with Bob's `PrivateKey`
+/// into Alice's signature on the same message, and vice versa, including
+/// signatures made before or outside the delegation. Only use it between
+/// parties that accept signing for each other with their own keys, and
+/// give it to proxies as carefully as the keys themselves.
+///
+/// `to_alice` and `to_bob` return the unidirectional `ResigningKey` of each
+/// direction, so either direction can be checked and split like any other
+/// re-signing key. Unlike Penumbral, a signature is re-signed from the other
+/// party's own key rather than from a designated key.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct BidirectionalResigningKey(Scalar);
+
+impl BidirectionalResigningKey {
+    /// Combines Alice's and Bob's shares into a `BidirectionalResigningKey`.
+    ///
+    /// Returns `Error::InvalidFragmentIndex` if a share is a fragment of a
+    /// re-signing key, and `Error::NonCanonicalScalar` if Bob's share is zero.
+    pub fn new(
+        alice_share: &ResigningKey,
+        bob_share: &ResigningKey,
+    ) -> Result<BidirectionalResigningKey, Error> {
+        if alice_share.1.is_some() || bob_share.1.is_some() {
+            return Err(Error::InvalidFragmentIndex);
+        }
+        let bob_inverse =
+            Option::<Scalar>::from(bob_share.0.invert()).ok_or(Error::NonCanonicalScalar)?;
+        Ok(BidirectionalResigningKey(alice_share.0 * bob_inverse))
+    }
+
+    /// Returns the `ResigningKey` that re-signs Bob's signatures to Alice's
+    /// key: $\sigma_A = (a / b) \cdot \sigma_B$.
+    pub fn to_alice(&self) -> ResigningKey {
+        ResigningKey(self.0, None)
+    }
+
+    /// Returns the `ResigningKey` that re-signs Alice's signatures to Bob's
+    /// key: $\sigma_B = (b / a) \cdot \sigma_A$.
+    pub fn to_bob(&self) -> ResigningKey {
+        let inverse = Option::<Scalar>::from(self.0.invert())
+            .expect("the key is never zero, as `new` and `from_bytes` reject zero");
+        ResigningKey(inverse, None)
+    }
+
+    /// Serializes the `BidirectionalResigningKey` to an array of 32 bytes.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Deserializes from a `&[u8]` to a `BidirectionalResigningKey`.
+    /// This returns an `Error` if the input is not 32 bytes long, or is not
+    /// a canonical, non-zero scalar.
+    pub fn from_bytes(bytes: &[u8]) -> Result<BidirectionalResigningKey, Error> {
+        if bytes.len() != SCALAR_BYTES_LENGTH {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let key = scalar_from_bytes(bytes)?;
+        if key == Scalar::zero() {
+            return Err(Error::NonCanonicalScalar);
+        }
+        Ok(BidirectionalResigningKey(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::{Signature, VerificationResult};
+    use crate::keys::PrivateKey;
+    use crate::traits::{PRSKey, ThresholdKey, ThresholdSignature};
+    use bls12_381::G2Affine;
+
+    #[test]
+    fn test_bidirectional_re_signature() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        let alice_share = priv_alice.bidirectional_resigning_share(&pub_bob);
+        let bob_share = priv_bob.bidirectional_resigning_share(&pub_alice);
+        let rekey = BidirectionalResigningKey::new(&alice_share, &bob_share).unwrap();
+
+        // Bob to Alice
+        let sig_bob = priv_bob.sign(&msg);
+        let sig_alice = rekey.to_alice().resign(&sig_bob);
+        assert_eq!(sig_alice, priv_alice.sign(&msg));
+        assert_eq!(
+            pub_alice.verify(&msg, &sig_alice),
+            VerificationResult::Valid
+        );
+        assert_eq!(
+            rekey.to_alice().resign_checked(&pub_bob, &msg, &sig_bob),
+            Ok(sig_alice)
+        );
+
+        // Alice to Bob
+        let sig_bob_resigned = rekey.to_bob().resign(&priv_alice.sign(&msg));
+        assert_eq!(sig_bob_resigned, sig_bob);
+        assert_eq!(
+            pub_bob.verify(&msg, &sig_bob_resigned),
+            VerificationResult::Valid
+        );
+        assert_eq!(
+            rekey.to_bob().resign_checked(&pub_alice, &msg, &sig_bob),
+            Err(Error::InvalidSignature)
+        );
+
+        // The shares can be combined in the other order, which swaps the
+        // directions.
+        let reversed = BidirectionalResigningKey::new(&bob_share, &alice_share).unwrap();
+        assert_eq!(reversed.to_alice(), rekey.to_bob());
+        assert_eq!(reversed.to_bob(), rekey.to_alice());
+
+        // Either direction can be split among proxies.
+        let fragments = rekey.to_bob().split(2, 3);
+        let sig_fragments = fragments
+            .iter()
+            .map(|fragment| fragment.resign(&sig_alice))
+            .collect::<Vec<_>>();
+        assert_eq!(Signature::assemble(&sig_fragments[1..]), Ok(sig_bob));
+    }
+
+    #[test]
+    fn test_shares_do_not_resign_designated_signatures() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // A share isn't Alice's unidirectional re-signing key, so it doesn't
+        // re-sign Bob's Penumbral designated signatures to her key.
+        let alice_share = priv_alice.bidirectional_resigning_share(&pub_bob);
+        assert_ne!(alice_share, priv_alice.resigning_key(&pub_bob));
+        let sig_designated = priv_bob.designated_key(&pub_alice).sign(&msg);
+        assert_ne!(alice_share.resign(&sig_designated), priv_alice.sign(&msg));
+        assert_eq!(
+            pub_alice.verify(&msg, &alice_share.resign(&sig_designated)),
+            VerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_bidirectional_resigning_key_serialization() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let alice_share = priv_alice.bidirectional_resigning_share(&priv_bob.public_key());
+        let bob_share = priv_bob.bidirectional_resigning_share(&priv_alice.public_key());
+        let rekey = BidirectionalResigningKey::new(&alice_share, &bob_share).unwrap();
+
+        let bytes = rekey.to_bytes();
+        assert_eq!(BidirectionalResigningKey::from_bytes(&bytes), Ok(rekey));
+        assert_eq!(
+            BidirectionalResigningKey::from_bytes(&bytes[1..]),
+            Err(Error::InvalidLength(31))
+        );
+        assert_eq!(
+            BidirectionalResigningKey::from_bytes(&[0u8; 32]),
+            Err(Error::NonCanonicalScalar)
+        );
+
+        assert_eq!(
+            BidirectionalResigningKey::new(&alice_share, &ResigningKey(Scalar::zero(), None)),
+            Err(Error::NonCanonicalScalar)
+        );
+        assert_eq!(
+            BidirectionalResigningKey::new(&alice_share.split(2, 3)[0], &bob_share),
+            Err(Error::InvalidFragmentIndex)
+        );
+    }
+}
@@ -1,11 +1,21 @@
-use bls12_381::{pairing, G1Affine, G2Affine, G2Projective, Scalar};
+use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
+use core::iter::once;
 
+use crate::error::Error;
 use crate::keys::{PrivateKey, PublicKey};
 use crate::traits::ThresholdSignature;
-use crate::utils::lambda_coeff;
+use crate::utils::{
+    basis_coeff, fragment_id, fragment_index_from_bytes, fragment_index_length,
+    fragment_index_to_bytes, fragment_indices, lambda_coeff,
+};
 
 const G2_POINT_BYTES_LENGTH: usize = 96;
 
+/// The most subsets of fragments that `Signature::assemble_robust` tries
+/// before giving up, so that whoever submits fragments can't force it to
+/// search through exponentially many of them.
+pub const MAX_ROBUST_ASSEMBLY_SUBSETS: usize = 1024;
+
 /// This type represents the output of a Signature verification.
 ///
 /// By representing signature verification in an `enum` like this, we are able
@@ -21,94 +31,271 @@ pub enum VerificationResult {
 /// A `Signature` is an Affine element of the G_2 group on the BLS12-381 curve.
 /// We have an `Option<Scalar>` field for a Fragment ID in the case of Threshold signatures.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-pub struct Signature(pub(crate) G2Affine, Option<Scalar>);
+pub struct Signature(pub(crate) G2Affine, pub(crate) Option<Scalar>);
 
 impl Signature {
     /// Creates a `Signature` and returns it by signing the `message_element`
     /// with the provided `private_key`.
     ///
-    /// The preferred API to sign messages is in `PrivateKey.sign`.
-    ///
-    /// Presently, the API for hashing to the G_2 group of BLS12-381 is not
-    /// implemented (see https://github.com/nucypher/NuBLS/issues/1). Therefore,
-    /// the message must be prehashed before verification and signing.
+    /// The preferred API to sign messages is in `PrivateKey.sign`, or
+    /// `PrivateKey.sign_message` to sign raw message bytes.
     ///
-    /// TODO: Implement hash_to_curve
+    /// The `message_element` must already be mapped to the G_2 group of
+    /// BLS12-381, e.g. with `hash_to_g2`.
     pub(crate) fn new(private_key: &PrivateKey, message_element: &G2Affine) -> Signature {
-        Signature((message_element * &private_key.0).into(), private_key.1)
+        Signature((message_element * private_key.0).into(), private_key.1)
     }
 
     /// Attempts to verify the signature given a `message_element` and a `public_key`.
     /// Returns a `VerificationResult::Valid` if the `message_element` and `public_key`
     /// are correct, and a `VerificationResult::Invalid` if they are not.
     ///
-    /// The preferred API to verify signatures is in `public_key.verify`.
+    /// The preferred API to verify signatures is in `public_key.verify`, or
+    /// `public_key.verify_message` to verify raw message bytes.
     ///
-    /// Presently, the API for hashing to the G_2 group of BLS12-381 is not
-    /// implemented (see https://github.com/nucypher/NuBLS/issues/1). Therefore,
-    /// the message must be prehashed before verification and signing.
-    ///
-    /// TODO: Implement hash_to_curve.
+    /// The `message_element` must already be mapped to the G_2 group of
+    /// BLS12-381, e.g. with `hash_to_g2`.
     pub(crate) fn verify(
         &self,
         public_key: &PublicKey,
         message_element: &G2Affine,
     ) -> VerificationResult {
-        let c_1 = pairing(&public_key.0, &message_element);
-        let c_2 = pairing(&G1Affine::generator(), &self.0);
+        VerificationResult::from(self.pairing_check(&[public_key.0], &[*message_element]))
+    }
+
+    /// Aggregates the `signatures` of independent signers into a single
+    /// `Signature` by summing them.
+    ///
+    /// The aggregate can be verified with `aggregate_verify` when every signer
+    /// signed a different message, or with `fast_aggregate_verify` when they
+    /// all signed the same message. Aggregating an empty slice returns the
+    /// identity, which never verifies.
+    ///
+    /// Note: This is not the same as `ThresholdSignature::assemble`, and the
+    /// fragment IDs of the `signatures` are not used.
+    pub fn aggregate(signatures: &[Signature]) -> Signature {
+        let aggregate = signatures
+            .iter()
+            .fold(G2Projective::identity(), |acc, signature| acc + signature.0);
+        Signature(aggregate.into(), None)
+    }
 
-        VerificationResult::from(c_1 == c_2)
+    /// Attempts to verify an aggregate signature, where the `i`th signer with
+    /// `public_keys[i]` signed `message_elements[i]`.
+    ///
+    /// This checks that `e(G_1, signature) == prod(e(public_keys[i], message_elements[i]))`
+    /// with a single multi-Miller loop and one final exponentiation.
+    /// Returns a `VerificationResult::Invalid` if there are no signers or if
+    /// the number of public keys and messages differ.
+    ///
+    /// Note: To be secure against rogue key attacks, either the messages must
+    /// be distinct or the signers must have proven possession of their keys.
+    /// `Ciphersuite::aggregate_verify` enforces these rules.
+    pub fn aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        message_elements: &[G2Affine],
+    ) -> VerificationResult {
+        if public_keys.is_empty() || public_keys.len() != message_elements.len() {
+            return VerificationResult::Invalid;
+        }
+        let g1_points = public_keys
+            .iter()
+            .map(|public_key| public_key.0)
+            .collect::<Vec<_>>();
+        VerificationResult::from(self.pairing_check(&g1_points, message_elements))
+    }
+
+    /// Attempts to verify an aggregate signature where every signer with a
+    /// key in `public_keys` signed the same `message_element`.
+    ///
+    /// The public keys are aggregated first, so this costs a single
+    /// two-term multi-Miller loop regardless of the number of signers.
+    /// Returns a `VerificationResult::Invalid` if there are no signers.
+    ///
+    /// Note: This is only secure against rogue key attacks if every signer
+    /// has proven possession of their private key.
+    pub fn fast_aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        message_element: &G2Affine,
+    ) -> VerificationResult {
+        if public_keys.is_empty() {
+            return VerificationResult::Invalid;
+        }
+        let aggregate_key = PublicKey::aggregate(public_keys);
+        VerificationResult::from(self.pairing_check(&[aggregate_key.0], &[*message_element]))
+    }
+
+    /// Assembles a `Signature` over `message_element` from signature
+    /// `fragments`, some of which may be invalid, and returns it along with
+    /// the positions in `fragments` of the faulty ones.
+    ///
+    /// Unlike `ThresholdSignature::assemble`, this searches for `threshold`
+    /// fragments that assemble to a `Signature` valid under `public_key`.
+    /// The remaining fragments are faulty unless they are consistent with
+    /// those `threshold` fragments, so every faulty fragment is found.
+    ///
+    /// When the `PublicKey` of each fragment is known (see
+    /// `PublicKey::verify_fragment`), passing them as `fragment_keys` rules
+    /// out invalid fragments upfront, so the search takes a single pairing
+    /// check. Without them, this tries the subsets of `threshold` fragments
+    /// in turn, at the cost of one pairing check each. There are
+    /// $\binom{n}{t}$ such subsets of $n$ fragments, e.g. about $1.4 \cdot
+    /// 10^{11}$ for 40 fragments and a threshold of 20, so the search gives
+    /// up after `MAX_ROBUST_ASSEMBLY_SUBSETS` subsets. Pass `fragment_keys`
+    /// whenever the fragments come from untrusted parties.
+    ///
+    /// Returns `Error::NotEnoughFragments` if no subset of the `fragments`
+    /// assembles to a valid `Signature`, or if none of the first
+    /// `MAX_ROBUST_ASSEMBLY_SUBSETS` subsets does.
+    pub fn assemble_robust(
+        fragments: &[Signature],
+        threshold: usize,
+        public_key: &PublicKey,
+        message_element: &G2Affine,
+        fragment_keys: Option<&[PublicKey]>,
+    ) -> Result<(Signature, Vec<usize>), Error> {
+        let candidates = fragments
+            .iter()
+            .enumerate()
+            .filter(|(_, fragment)| match (fragment.1, fragment_keys) {
+                (Some(_), Some(keys)) => keys.iter().any(|key| {
+                    key.verify_fragment(message_element, fragment) == VerificationResult::Valid
+                }),
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        if threshold == 0 || candidates.len() < threshold {
+            return Err(Error::NotEnoughFragments);
+        }
+
+        // Walk through every `threshold`-subset of the candidates in
+        // lexicographic order, skipping those with repeated fragment IDs.
+        let mut combination = (0..threshold).collect::<Vec<_>>();
+        for _ in 0..MAX_ROBUST_ASSEMBLY_SUBSETS {
+            let subset = combination
+                .iter()
+                .map(|&c| fragments[candidates[c]])
+                .collect::<Vec<_>>();
+            let indices = subset
+                .iter()
+                .map(|fragment| fragment.1.unwrap())
+                .collect::<Vec<_>>();
+            let distinct = indices
+                .iter()
+                .enumerate()
+                .all(|(i, index)| !indices[..i].contains(index));
+            if distinct {
+                let signature = Signature::assemble(&subset[..])?;
+                if signature.verify(public_key, message_element) == VerificationResult::Valid {
+                    let faulty = (0..fragments.len())
+                        .filter(|position| {
+                            !combination.iter().any(|&c| candidates[c] == *position)
+                                && !is_consistent(&fragments[*position], &subset[..], &indices[..])
+                        })
+                        .collect();
+                    return Ok((signature, faulty));
+                }
+            }
+            if !next_combination(&mut combination[..], candidates.len()) {
+                break;
+            }
+        }
+        Err(Error::NotEnoughFragments)
+    }
+
+    /// Checks that `e(G_1, self) == prod(e(g1_points[i], g2_points[i]))` by
+    /// computing `prod(e(g1_points[i], g2_points[i])) * e(-G_1, self)` in one
+    /// multi-Miller loop and comparing the result to the identity.
+    pub(crate) fn pairing_check(&self, g1_points: &[G1Affine], g2_points: &[G2Affine]) -> bool {
+        let neg_generator = -G1Affine::generator();
+        let prepared = g2_points
+            .iter()
+            .chain(once(&self.0))
+            .map(|&point| G2Prepared::from(point))
+            .collect::<Vec<_>>();
+        let terms = g1_points
+            .iter()
+            .chain(once(&neg_generator))
+            .zip(prepared.iter())
+            .collect::<Vec<_>>();
+
+        multi_miller_loop(&terms[..]).final_exponentiation() == Gt::identity()
+    }
+
+    /// Returns the fragment ID of a signature fragment, which is the fragment
+    /// ID of the `PrivateKey` fragment that made it.
+    pub fn fragment_id(&self) -> Option<u32> {
+        self.1.as_ref().and_then(fragment_id)
+    }
+
+    /// Returns the length of the `Signature` once serialized with `to_bytes`.
+    pub fn serialized_size(&self) -> usize {
+        G2_POINT_BYTES_LENGTH + self.1.as_ref().map_or(0, fragment_index_length)
     }
 
     /// Serializes the `Signature` by filling a buffer passed as an argument.
-    /// If the buffer is not big enough, this method will panic.
+    /// If the buffer is shorter than `serialized_size`, this returns
+    /// `Error::InvalidLength`.
     ///
-    /// A `Signature` can be serialized in two ways:
+    /// A `Signature` can be serialized in three ways:
     ///  1. 96 bytes -- This is the case when a `Signature` is _not_ a fragment
-    ///  to a threshold signature.
+    ///     to a threshold signature.
     ///
-    ///  2. 128 bytes -- This is the case when a `Signature` _is_ a fragment
-    ///  to a threshold signature. This allows us to store its fragment ID for
-    ///  Shamir's Secret Sharing.
+    ///  2. 100 bytes -- This is the case when a `Signature` _is_ a fragment
+    ///     to a threshold signature. This allows us to store its fragment ID for
+    ///     Shamir's Secret Sharing, as a 4 byte big-endian integer.
     ///
-    ///  Note: This serialization will probably change in the future.
-    ///  See https://github.com/nucypher/NuBLS/issues/3
-    pub fn to_bytes(&self, buff: &mut [u8]) {
+    ///  3. 128 bytes -- This is the case for fragments made by a fragment with
+    ///     a random 32 byte index, as split by older versions of NuBLS.
+    pub fn to_bytes(&self, buff: &mut [u8]) -> Result<(), Error> {
+        let length = self.serialized_size();
+        if buff.len() < length {
+            return Err(Error::InvalidLength(buff.len()));
+        }
+
         buff[0..96].copy_from_slice(&self.0.to_compressed()[..]);
         if let Some(fragment_index) = self.1 {
-            buff[96..128].copy_from_slice(&fragment_index.to_bytes()[..]);
+            fragment_index_to_bytes(&fragment_index, &mut buff[96..length]);
         }
+        Ok(())
     }
 
-    /// Deserializes from a `&[u8; 96]` to a `Signature`.
-    /// This will panic if the input is not canonical.
+    /// Deserializes from a `&[u8]` to a `Signature`.
+    /// This returns an `Error` if the input is not 96, 100 or 128 bytes long,
+    /// or is not a valid, non-identity point in G_2.
     ///
-    /// A `Signature` can be serialized in two ways:
+    /// A `Signature` can be serialized in three ways:
     ///  1. 96 bytes -- This is the case when a `Signature` is _not_ a fragment
-    ///  to a threshold signature.
-    ///
-    ///  2. 128 bytes -- This is the case when a `Signature` _is_ a fragment
-    ///  to a threshold signature. This allows us to store its fragment ID for
-    ///  Shamir's Secret Sharing.
-    ///
-    ///  Note: This serialization will probably change in the future.
-    ///  See https://github.com/nucypher/NuBLS/issues/3
-    pub fn from_bytes(bytes: &[u8]) -> Signature {
-        let mut point_bytes = [0u8; 96];
-        let fragment_index: Option<Scalar>;
-        if bytes.len() == G2_POINT_BYTES_LENGTH {
-            point_bytes.copy_from_slice(&bytes);
-            fragment_index = None
-        } else {
-            let mut index_bytes = [0u8; 32];
-            point_bytes.copy_from_slice(&bytes[0..G2_POINT_BYTES_LENGTH]);
-            index_bytes.copy_from_slice(&bytes[G2_POINT_BYTES_LENGTH..128]);
-            fragment_index = Some(Scalar::from_bytes(&index_bytes).unwrap());
+    ///     to a threshold signature.
+    ///
+    ///  2. 100 bytes -- This is the case when a `Signature` _is_ a fragment
+    ///     to a threshold signature. This allows us to store its fragment ID for
+    ///     Shamir's Secret Sharing, as a 4 byte big-endian integer.
+    ///
+    ///  3. 128 bytes -- This is the case for fragments made by a fragment with
+    ///     a random 32 byte index, as split by older versions of NuBLS.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, Error> {
+        let fragment_index = match bytes.len() {
+            G2_POINT_BYTES_LENGTH => None,
+            100 | 128 => Some(fragment_index_from_bytes(&bytes[G2_POINT_BYTES_LENGTH..])?),
+            length => return Err(Error::InvalidLength(length)),
+        };
+        let mut point_bytes = [0u8; G2_POINT_BYTES_LENGTH];
+        point_bytes.copy_from_slice(&bytes[0..G2_POINT_BYTES_LENGTH]);
+
+        let point: G2Affine = Option::from(G2Affine::from_compressed_unchecked(&point_bytes))
+            .ok_or(Error::PointNotOnCurve)?;
+        if bool::from(point.is_identity()) {
+            return Err(Error::IdentityPoint);
         }
-        Signature(
-            G2Affine::from_compressed(&point_bytes).unwrap(),
-            fragment_index,
-        )
+        if !bool::from(point.is_torsion_free()) {
+            return Err(Error::PointNotInSubgroup);
+        }
+        Ok(Signature(point, fragment_index))
     }
 }
 
@@ -125,37 +312,54 @@ impl ThresholdSignature for Signature {
     /// or else the assembled `Signature` will be invalid.
     ///
     /// This calculates the final signature by using Lagrange basis polynomials.
-    fn assemble(fragments: &[Signature]) -> Signature {
-        // First, we generate the fragment indices.
-        // We create a buffer to hold fragment indices of size 256 because
-        // our limit to fragments is 256.
-        // This can be significantly improved, for more info see
-        // https://github.com/nucypher/NuBLS/issues/3.
-        let mut fragment_indices = [Scalar::zero(); 256];
-        for i in 0..fragments.len() {
-            fragment_indices[i] = fragments[i].1.unwrap();
-        }
+    fn assemble(fragments: &[Signature]) -> Result<Signature, Error> {
+        // First, we collect and check the fragment indices.
+        let fragment_indices = fragment_indices(fragments.iter().map(|fragment| fragment.1))?;
 
         // Then we evaluate the lagrange basis polynomials and assemble the
         // full `Signature`.
-        // Note: we limit the `fragments_indices` slice to the length of the 
-        // `fragments` slice.
         let mut result = G2Projective::identity();
-        for fragment in fragments.iter() {
+        for (fragment, fragment_index) in fragments.iter().zip(fragment_indices.iter()) {
             // The BLS12_381 API doesn't use additive notation, apparently.
-            result +=
-                fragment.0 * lambda_coeff(&fragment.1.unwrap(), &fragment_indices[..fragments.len()]);
+            result += fragment.0 * lambda_coeff(fragment_index, &fragment_indices[..]);
         }
-        Signature(result.into(), None)
+        Ok(Signature(result.into(), None))
     }
 
     /// Returns whether or not this is a fragment of a threshold signature.
     fn is_fragment(&self) -> bool {
-        match self.1 {
-            Some(_) => true,
-            None => false,
+        self.1.is_some()
+    }
+}
+
+/// Checks that `fragment` lies on the polynomial interpolated "in the
+/// exponent" through the `subset` of fragments with `indices`.
+fn is_consistent(fragment: &Signature, subset: &[Signature], indices: &[Scalar]) -> bool {
+    match fragment.1 {
+        Some(x) => {
+            let expected = subset.iter().fold(G2Projective::identity(), |acc, other| {
+                acc + other.0 * basis_coeff(&other.1.unwrap(), indices, &x)
+            });
+            G2Affine::from(expected) == fragment.0
+        }
+        None => false,
+    }
+}
+
+/// Advances `combination`, a sorted selection of indices from `0..n`, to the
+/// next one in lexicographic order. Returns `false` once it was the last.
+fn next_combination(combination: &mut [usize], n: usize) -> bool {
+    let k = combination.len();
+    for i in (0..k).rev() {
+        if combination[i] < n - k + i {
+            combination[i] += 1;
+            for j in i + 1..k {
+                combination[j] = combination[j - 1] + 1;
+            }
+            return true;
         }
     }
+    false
 }
 
 impl From<bool> for VerificationResult {
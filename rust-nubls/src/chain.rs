@@ -0,0 +1,327 @@
+use bls12_381::{G2Affine, Scalar};
+
+use crate::bls::{Signature, VerificationResult};
+use crate::error::Error;
+use crate::keys::{PublicKey, SCALAR_BYTES_LENGTH};
+use crate::prs::ResigningKey;
+
+/// The length of a hop of a `ResigningChain` once serialized, as a tag byte
+/// followed by its 32 byte re-signing key.
+const HOP_BYTES_LENGTH: usize = 1 + SCALAR_BYTES_LENGTH;
+
+/// A `ResigningChain` is a chain of re-signing keys that re-signs a signature
+/// across several hops of delegation, e.g. from Carol to Bob to Alice.
+///
+/// Every hop but the last is a re-signing key from one party's designated key
+/// to the next party's designated key, from `PRSKey::hop_resigning_key`. The
+/// last hop is an ordinary re-signing key from `PRSKey::resigning_key`, so the
+/// chain re-signs a signature under the first party's designated key to
+/// exactly the signature of the last party.
+///
+/// The hops are applied in order, and compose into a single re-signing key.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ResigningChain(Vec<ResigningKey>);
+
+impl ResigningChain {
+    /// Builds a `ResigningChain` from its `hops`, in the order that they re-sign.
+    ///
+    /// Returns `Error::InvalidLength` if there are no hops,
+    /// `Error::InvalidFragmentIndex` if a hop is a fragment of a re-signing key,
+    /// and `Error::NonCanonicalScalar` if a hop is zero, which would map every
+    /// signature to the identity.
+    pub fn new(hops: Vec<ResigningKey>) -> Result<ResigningChain, Error> {
+        if hops.is_empty() {
+            return Err(Error::InvalidLength(0));
+        }
+        if hops.iter().any(|hop| hop.1.is_some()) {
+            return Err(Error::InvalidFragmentIndex);
+        }
+        if hops.iter().any(|hop| hop.0 == Scalar::zero()) {
+            return Err(Error::NonCanonicalScalar);
+        }
+        Ok(ResigningChain(hops))
+    }
+
+    /// Returns the re-signing keys of the chain's hops, in order.
+    pub fn hops(&self) -> &[ResigningKey] {
+        &self.0[..]
+    }
+
+    /// Composes the chain's hops into a single re-signing key, which re-signs
+    /// like the whole chain.
+    pub fn resigning_key(&self) -> ResigningKey {
+        let key = self.0.iter().fold(Scalar::one(), |acc, hop| acc * hop.0);
+        ResigningKey(key, None)
+    }
+
+    /// Re-signs a `Signature` under the first party's designated key through
+    /// every hop of the chain, to the last party's key.
+    pub fn resign(&self, signature: &Signature) -> Signature {
+        self.0
+            .iter()
+            .fold(*signature, |signature, hop| hop.resign(&signature))
+    }
+
+    /// Verifies a `signature` on the `message_element` under the first party's
+    /// `designated_pubkey` before re-signing it through the chain.
+    ///
+    /// Returns `Error::InvalidSignature` if the signature doesn't verify.
+    pub fn resign_checked(
+        &self,
+        designated_pubkey: &PublicKey,
+        message_element: &G2Affine,
+        signature: &Signature,
+    ) -> Result<Signature, Error> {
+        match designated_pubkey.verify(message_element, signature) {
+            VerificationResult::Valid => Ok(self.resign(signature)),
+            VerificationResult::Invalid => Err(Error::InvalidSignature),
+        }
+    }
+
+    /// Serializes the `ResigningChain` as the concatenation of its hops,
+    /// each tagged and serialized with `ResigningKey::to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buff = vec![0u8; self.0.len() * HOP_BYTES_LENGTH];
+        for (hop, hop_buff) in self.0.iter().zip(buff.chunks_mut(HOP_BYTES_LENGTH)) {
+            hop.to_bytes(hop_buff)
+                .expect("hops are never fragments, so fit in their 33 bytes");
+        }
+        buff
+    }
+
+    /// Deserializes a `ResigningChain` from the concatenation of its hops'
+    /// 33 byte tagged re-signing keys.
+    /// This returns an `Error` if the input is empty, is not a multiple of 33
+    /// bytes long, or contains a hop that is not a serialized `ResigningKey`,
+    /// or is not a canonical, non-zero key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ResigningChain, Error> {
+        if bytes.is_empty() || bytes.len() % HOP_BYTES_LENGTH != 0 {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let hops = bytes
+            .chunks(HOP_BYTES_LENGTH)
+            .map(ResigningKey::from_bytes)
+            .collect::<Result<Vec<_>, Error>>()?;
+        ResigningChain::new(hops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kdf::DesignatedKeyKdf;
+    use crate::keys::PrivateKey;
+    use crate::traits::PRSKey;
+
+    #[test]
+    fn test_resigning_chain() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+        let priv_carol = PrivateKey::random();
+        let pub_carol = priv_carol.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // Alice delegates to Bob, who delegates on to Carol.
+        let rekey_ba = priv_alice.resigning_key(&pub_bob);
+        let rekey_cb = priv_bob.hop_resigning_key(&pub_carol, &pub_alice);
+        let chain = ResigningChain::new(vec![rekey_cb, rekey_ba]).unwrap();
+
+        // Carol signs with her designated key for Bob, and the chain re-signs
+        // it to Alice's own signature.
+        let designated_carol = priv_carol.designated_key(&pub_bob);
+        let sig_c = designated_carol.sign(&msg);
+        let sig_a = chain.resign(&sig_c);
+        assert_eq!(sig_a, priv_alice.sign(&msg));
+        assert_eq!(pub_alice.verify(&msg, &sig_a), VerificationResult::Valid);
+
+        // Bob's hop alone re-signs to his designated key for Alice.
+        assert_eq!(
+            rekey_cb.resign(&sig_c),
+            priv_bob.designated_key(&pub_alice).sign(&msg)
+        );
+
+        // The chain composes into a single re-signing key.
+        assert_eq!(chain.resigning_key().resign(&sig_c), sig_a);
+
+        // Checked re-signing rejects signatures not under Carol's designated key.
+        assert_eq!(
+            chain.resign_checked(&designated_carol.public_key(), &msg, &sig_c),
+            Ok(sig_a)
+        );
+        let pub_designated_carol = designated_carol.public_key();
+        assert_eq!(
+            chain.resign_checked(&pub_designated_carol, &msg, &priv_carol.sign(&msg)),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_longer_resigning_chain() {
+        let privs = (0..4).map(|_| PrivateKey::random()).collect::<Vec<_>>();
+        let pubs = privs.iter().map(|key| key.public_key()).collect::<Vec<_>>();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // Each party delegates to the next, from privs[3] down to privs[0].
+        let chain = ResigningChain::new(vec![
+            privs[2].hop_resigning_key(&pubs[3], &pubs[1]),
+            privs[1].hop_resigning_key(&pubs[2], &pubs[0]),
+            privs[0].resigning_key(&pubs[1]),
+        ])
+        .unwrap();
+        let sig = privs[3].designated_key(&pubs[2]).sign(&msg);
+        assert_eq!(chain.resign(&sig), privs[0].sign(&msg));
+    }
+
+    #[test]
+    fn test_resigning_chain_with_kdf_and_epochs() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+        let priv_carol = PrivateKey::random();
+        let pub_carol = priv_carol.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+        let sig_a = priv_alice.sign(&msg);
+
+        // Every hop derives its designated keys with the same KDF.
+        let kdf = DesignatedKeyKdf::HkdfSha256(b"NUBLS_TEST_APP_");
+        let chain = ResigningChain::new(vec![
+            priv_bob.hop_resigning_key_with_kdf(&pub_carol, &pub_alice, kdf),
+            priv_alice.resigning_key_with_kdf(&pub_bob, kdf),
+        ])
+        .unwrap();
+        let sig_c = priv_carol.designated_key_with_kdf(&pub_bob, kdf).sign(&msg);
+        assert_eq!(chain.resign(&sig_c), sig_a);
+        assert_ne!(
+            chain.resign(&priv_carol.designated_key(&pub_bob).sign(&msg)),
+            sig_a
+        );
+        assert_eq!(
+            priv_bob.hop_resigning_key_with_kdf(
+                &pub_carol,
+                &pub_alice,
+                DesignatedKeyKdf::default()
+            ),
+            priv_bob.hop_resigning_key(&pub_carol, &pub_alice)
+        );
+
+        // Bob scopes Carol to his epoch, and Alice scopes Bob to hers.
+        let hop = priv_bob
+            .hop_resigning_key_for_epoch(&pub_carol, &pub_alice, b"bob-1", b"alice-1", kdf)
+            .unwrap();
+        let chain = ResigningChain::new(vec![
+            hop,
+            priv_alice
+                .resigning_key_for_epoch(&pub_bob, b"alice-1", kdf)
+                .unwrap(),
+        ])
+        .unwrap();
+        let sig_c = priv_carol
+            .designated_key_for_epoch(&pub_bob, b"bob-1", kdf)
+            .unwrap()
+            .sign(&msg);
+        assert_eq!(chain.resign(&sig_c), sig_a);
+
+        // Bob revokes Carol by moving her to a new epoch.
+        let revoked = priv_carol
+            .designated_key_for_epoch(&pub_bob, b"bob-2", kdf)
+            .unwrap()
+            .sign(&msg);
+        assert_ne!(chain.resign(&revoked), sig_a);
+
+        // Alice revokes Bob's hop, and with it Carol, with a new epoch.
+        let chain = ResigningChain::new(vec![
+            hop,
+            priv_alice
+                .resigning_key_for_epoch(&pub_bob, b"alice-2", kdf)
+                .unwrap(),
+        ])
+        .unwrap();
+        assert_ne!(chain.resign(&sig_c), sig_a);
+
+        // Legacy hops can't be scoped to epochs.
+        assert_eq!(
+            priv_bob.hop_resigning_key_for_epoch(
+                &pub_carol,
+                &pub_alice,
+                b"bob-1",
+                b"alice-1",
+                DesignatedKeyKdf::Legacy
+            ),
+            Err(Error::UnsupportedEpoch)
+        );
+    }
+
+    #[test]
+    fn test_resigning_chain_serialization() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let pub_carol = PrivateKey::random().public_key();
+
+        let chain = ResigningChain::new(vec![
+            priv_bob.hop_resigning_key(&pub_carol, &priv_alice.public_key()),
+            priv_alice.resigning_key(&priv_bob.public_key()),
+        ])
+        .unwrap();
+        let bytes = chain.to_bytes();
+        assert_eq!(bytes.len(), 66);
+        assert_eq!(bytes[0], 0x02);
+        assert_eq!(bytes[33], 0x02);
+        assert_eq!(ResigningChain::from_bytes(&bytes), Ok(chain.clone()));
+        assert_eq!(chain.hops().len(), 2);
+        let mut hop = [0u8; 33];
+        chain.hops()[1].to_bytes(&mut hop).unwrap();
+        assert_eq!(&bytes[33..], &hop[..]);
+
+        assert_eq!(
+            ResigningChain::from_bytes(&[]),
+            Err(Error::InvalidLength(0))
+        );
+        assert_eq!(
+            ResigningChain::from_bytes(&bytes[..40]),
+            Err(Error::InvalidLength(40))
+        );
+        // Untagged 32 byte keys, as well as keys of the wrong type, are rejected.
+        assert_eq!(
+            ResigningChain::from_bytes(&bytes[1..]),
+            Err(Error::InvalidLength(65))
+        );
+        let mut wrong_type = bytes.clone();
+        wrong_type[33] = 0x01;
+        assert_eq!(
+            ResigningChain::from_bytes(&wrong_type),
+            Err(Error::InvalidKeyType(0x01))
+        );
+        let mut non_canonical = [0xffu8; 33];
+        non_canonical[0] = 0x02;
+        assert_eq!(
+            ResigningChain::from_bytes(&non_canonical),
+            Err(Error::NonCanonicalScalar)
+        );
+        let mut zero = [0u8; 33];
+        zero[0] = 0x02;
+        assert_eq!(
+            ResigningChain::from_bytes(&zero),
+            Err(Error::NonCanonicalScalar)
+        );
+
+        assert_eq!(ResigningChain::new(vec![]), Err(Error::InvalidLength(0)));
+        assert_eq!(
+            ResigningChain::new(vec![chain.hops()[0], ResigningKey(Scalar::zero(), None)]),
+            Err(Error::NonCanonicalScalar)
+        );
+        let fragments = priv_alice.split_resigning_key(&priv_bob.public_key(), 2, 3);
+        assert_eq!(
+            ResigningChain::new(fragments),
+            Err(Error::InvalidFragmentIndex)
+        );
+    }
+}
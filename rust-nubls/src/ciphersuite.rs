@@ -0,0 +1,341 @@
+use bls12_381::G2Affine;
+
+use crate::bls::{Signature, VerificationResult};
+use crate::hash_to_curve::hash_to_g2;
+use crate::keys::{PrivateKey, PublicKey};
+
+/// The ciphersuite ID (and DST) of the basic scheme.
+const BASIC_ID: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The ciphersuite ID (and DST) of the message augmentation scheme.
+const MESSAGE_AUGMENTATION_ID: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
+
+/// The ciphersuite ID (and DST) of the proof of possession scheme.
+const PROOF_OF_POSSESSION_ID: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// The DST that proofs of possession are made under in the proof of
+/// possession scheme. This keeps them separate from message signatures.
+pub(crate) const POP_TAG: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A `Ciphersuite` selects one of the three BLS signature schemes defined in
+/// the IETF BLS signature draft, for public keys in G_1 and signatures in G_2.
+///
+/// Each ciphersuite fixes the domain separation tag that messages are hashed
+/// to G_2 with, and the scheme-specific rules used to defend against rogue key
+/// attacks when verifying aggregate signatures:
+///
+///  1. `Basic` -- `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_`. Aggregate
+///     signatures are only accepted over distinct messages.
+///
+///  2. `MessageAugmentation` -- `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_`.
+///     Every message is prefixed with the signer's serialized `PublicKey`
+///     before signing, which makes the signed messages distinct.
+///
+///  3. `ProofOfPossession` -- `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`.
+///     Signers must prove possession of their private key, so no restriction
+///     is placed on aggregate signatures.
+///
+/// Signatures made under one ciphersuite will never verify under another.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Ciphersuite {
+    Basic,
+    MessageAugmentation,
+    ProofOfPossession,
+}
+
+impl Ciphersuite {
+    /// Returns the ciphersuite ID, which is also the domain separation tag
+    /// used to hash messages to G_2.
+    pub fn id(&self) -> &'static [u8] {
+        match self {
+            Ciphersuite::Basic => BASIC_ID,
+            Ciphersuite::MessageAugmentation => MESSAGE_AUGMENTATION_ID,
+            Ciphersuite::ProofOfPossession => PROOF_OF_POSSESSION_ID,
+        }
+    }
+
+    /// Signs the `message` with the `private_key` under this ciphersuite.
+    pub fn sign(&self, private_key: &PrivateKey, message: &[u8]) -> Signature {
+        let message_element = match self {
+            Ciphersuite::MessageAugmentation => {
+                self.hash_augmented(&private_key.public_key(), message)
+            }
+            _ => hash_to_g2(message, self.id()),
+        };
+        private_key.sign(&message_element)
+    }
+
+    /// Attempts to verify a `signature` on the `message` under this ciphersuite.
+    ///
+    /// Returns `VerificationResult::Invalid` if the `public_key` is the identity.
+    pub fn verify(
+        &self,
+        public_key: &PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> VerificationResult {
+        if !key_validate(public_key) {
+            return VerificationResult::Invalid;
+        }
+        let message_element = match self {
+            Ciphersuite::MessageAugmentation => self.hash_augmented(public_key, message),
+            _ => hash_to_g2(message, self.id()),
+        };
+        public_key.verify(&message_element, signature)
+    }
+
+    /// Attempts to verify an aggregate `signature` where the `i`th signer with
+    /// `public_keys[i]` signed `messages[i]`.
+    ///
+    /// Returns `VerificationResult::Invalid` if there are no signers, if the
+    /// number of public keys and messages differ, if any public key is the
+    /// identity, or, under the `Basic` ciphersuite, if the messages are not
+    /// all distinct.
+    pub fn aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        messages: &[&[u8]],
+        signature: &Signature,
+    ) -> VerificationResult {
+        if public_keys.is_empty() || public_keys.len() != messages.len() {
+            return VerificationResult::Invalid;
+        }
+        if !public_keys.iter().all(key_validate) {
+            return VerificationResult::Invalid;
+        }
+        if *self == Ciphersuite::Basic {
+            for (i, message) in messages.iter().enumerate() {
+                if messages[..i].contains(message) {
+                    return VerificationResult::Invalid;
+                }
+            }
+        }
+
+        let message_elements = public_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(public_key, message)| match self {
+                Ciphersuite::MessageAugmentation => self.hash_augmented(public_key, message),
+                _ => hash_to_g2(message, self.id()),
+            })
+            .collect::<Vec<_>>();
+        signature.aggregate_verify(public_keys, &message_elements)
+    }
+
+    /// Attempts to verify an aggregate `signature` where every signer with a
+    /// key in `public_keys` signed the same `message`.
+    ///
+    /// This is only defined for the `ProofOfPossession` ciphersuite, and the
+    /// caller must have checked each key with `PublicKey::verify_possession`
+    /// beforehand. Under the other ciphersuites this is vulnerable to rogue
+    /// key attacks, so it always returns `VerificationResult::Invalid`.
+    pub fn fast_aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        message: &[u8],
+        signature: &Signature,
+    ) -> VerificationResult {
+        if *self != Ciphersuite::ProofOfPossession {
+            return VerificationResult::Invalid;
+        }
+        if !public_keys.iter().all(key_validate) {
+            return VerificationResult::Invalid;
+        }
+        signature.fast_aggregate_verify(public_keys, &hash_to_g2(message, self.id()))
+    }
+
+    /// Hashes `public_key || message` to G_2, as the message augmentation
+    /// scheme signs it.
+    fn hash_augmented(&self, public_key: &PublicKey, message: &[u8]) -> G2Affine {
+        let augmented = [&public_key.0.to_compressed()[..], message].concat();
+        hash_to_g2(&augmented, self.id())
+    }
+}
+
+/// Checks that a `PublicKey` is valid, i.e. that it is not the identity.
+///
+/// `PublicKey`s are always in the G_1 subgroup, as deserialization checks it.
+pub(crate) fn key_validate(public_key: &PublicKey) -> bool {
+    !bool::from(public_key.0.is_identity())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::G1Affine;
+
+    const CIPHERSUITES: [Ciphersuite; 3] = [
+        Ciphersuite::Basic,
+        Ciphersuite::MessageAugmentation,
+        Ciphersuite::ProofOfPossession,
+    ];
+
+    #[test]
+    fn test_sign_and_verify() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+
+        for suite in CIPHERSUITES.iter() {
+            let sig = suite.sign(&priv_a, b"NuBLS!");
+            assert_eq!(
+                suite.verify(&pub_a, b"NuBLS!", &sig),
+                VerificationResult::Valid
+            );
+            assert_eq!(
+                suite.verify(&pub_a, b"Penumbral!", &sig),
+                VerificationResult::Invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_basic_matches_sign_message() {
+        let priv_a = PrivateKey::random();
+        assert_eq!(
+            Ciphersuite::Basic.sign(&priv_a, b"NuBLS!"),
+            priv_a.sign_message(b"NuBLS!")
+        );
+    }
+
+    #[test]
+    fn test_ciphersuites_are_domain_separated() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+
+        for signing_suite in CIPHERSUITES.iter() {
+            let sig = signing_suite.sign(&priv_a, b"NuBLS!");
+            for verifying_suite in CIPHERSUITES.iter() {
+                let expected = VerificationResult::from(signing_suite == verifying_suite);
+                assert_eq!(verifying_suite.verify(&pub_a, b"NuBLS!", &sig), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity_public_key_is_rejected() {
+        let identity = PublicKey(G1Affine::identity(), None);
+        let sig = Signature(G2Affine::identity(), None);
+
+        // The identity key "verifies" any identity signature with a raw
+        // pairing check, which is why ciphersuites validate keys first.
+        for suite in CIPHERSUITES.iter() {
+            assert_eq!(
+                suite.verify(&identity, b"NuBLS!", &sig),
+                VerificationResult::Invalid
+            );
+            assert_eq!(
+                suite.aggregate_verify(&[identity], &[&b"NuBLS!"[..]], &sig),
+                VerificationResult::Invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_aggregate_verify() {
+        let priv_keys = [
+            PrivateKey::random(),
+            PrivateKey::random(),
+            PrivateKey::random(),
+        ];
+        let pub_keys = [
+            priv_keys[0].public_key(),
+            priv_keys[1].public_key(),
+            priv_keys[2].public_key(),
+        ];
+        let messages: [&[u8]; 3] = [b"message 1", b"message 2", b"message 3"];
+
+        for suite in CIPHERSUITES.iter() {
+            let sigs = [
+                suite.sign(&priv_keys[0], messages[0]),
+                suite.sign(&priv_keys[1], messages[1]),
+                suite.sign(&priv_keys[2], messages[2]),
+            ];
+            let agg_sig = Signature::aggregate(&sigs);
+            assert_eq!(
+                suite.aggregate_verify(&pub_keys, &messages, &agg_sig),
+                VerificationResult::Valid
+            );
+
+            // Swapping the messages between signers must fail.
+            let swapped: [&[u8]; 3] = [messages[1], messages[0], messages[2]];
+            assert_eq!(
+                suite.aggregate_verify(&pub_keys, &swapped, &agg_sig),
+                VerificationResult::Invalid
+            );
+
+            // So must mismatched or empty inputs.
+            assert_eq!(
+                suite.aggregate_verify(&pub_keys[..2], &messages, &agg_sig),
+                VerificationResult::Invalid
+            );
+            assert_eq!(
+                suite.aggregate_verify(&[], &[], &agg_sig),
+                VerificationResult::Invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_fast_aggregate_verify() {
+        let priv_keys = [
+            PrivateKey::random(),
+            PrivateKey::random(),
+            PrivateKey::random(),
+        ];
+        let pub_keys = [
+            priv_keys[0].public_key(),
+            priv_keys[1].public_key(),
+            priv_keys[2].public_key(),
+        ];
+
+        // Signers prove possession of their keys before being aggregated.
+        for (priv_key, pub_key) in priv_keys.iter().zip(pub_keys.iter()) {
+            let proof = priv_key.prove_possession();
+            assert_eq!(pub_key.verify_possession(&proof), VerificationResult::Valid);
+        }
+
+        for suite in CIPHERSUITES.iter() {
+            let agg_sig = Signature::aggregate(&[
+                suite.sign(&priv_keys[0], b"NuBLS!"),
+                suite.sign(&priv_keys[1], b"NuBLS!"),
+                suite.sign(&priv_keys[2], b"NuBLS!"),
+            ]);
+
+            // Only the proof of possession scheme allows fast aggregation.
+            let expected = VerificationResult::from(*suite == Ciphersuite::ProofOfPossession);
+            assert_eq!(
+                suite.fast_aggregate_verify(&pub_keys, b"NuBLS!", &agg_sig),
+                expected
+            );
+            assert_eq!(
+                suite.fast_aggregate_verify(&pub_keys, b"Penumbral!", &agg_sig),
+                VerificationResult::Invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_aggregate_verify_repeated_messages() {
+        let priv_a = PrivateKey::random();
+        let priv_b = PrivateKey::random();
+        let pub_keys = [priv_a.public_key(), priv_b.public_key()];
+        let messages: [&[u8]; 2] = [b"NuBLS!", b"NuBLS!"];
+
+        for suite in CIPHERSUITES.iter() {
+            let agg_sig = Signature::aggregate(&[
+                suite.sign(&priv_a, messages[0]),
+                suite.sign(&priv_b, messages[1]),
+            ]);
+
+            // Only the basic scheme requires distinct messages.
+            let expected = match suite {
+                Ciphersuite::Basic => VerificationResult::Invalid,
+                _ => VerificationResult::Valid,
+            };
+            assert_eq!(
+                suite.aggregate_verify(&pub_keys, &messages, &agg_sig),
+                expected
+            );
+        }
+    }
+}
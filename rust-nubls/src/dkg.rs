@@ -0,0 +1,1136 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bls12_381::{G1Affine, G1Projective, Scalar};
+
+use crate::bls::VerificationResult;
+use crate::error::Error;
+use crate::keys::{PrivateKey, PublicKey, SCALAR_BYTES_LENGTH};
+use crate::utils::{fragment_index, interpolate, poly_eval, random_poly};
+use crate::vss::{BlindingShare, FeldmanCommitments, PedersenCommitments};
+
+/// The length of a participant ID once serialized, as a 4 byte big-endian
+/// integer.
+const PARTICIPANT_ID_BYTES_LENGTH: usize = 4;
+
+/// The tag bytes of the serialized `DkgPayload`s, one per kind.
+const PEDERSEN_COMMITMENTS_TAG: u8 = 0x01;
+const SHARE_TAG: u8 = 0x02;
+const COMPLAINT_TAG: u8 = 0x03;
+const JUSTIFICATION_TAG: u8 = 0x04;
+const FELDMAN_COMMITMENTS_TAG: u8 = 0x05;
+const FELDMAN_COMPLAINT_TAG: u8 = 0x06;
+const RECONSTRUCTION_SHARE_TAG: u8 = 0x07;
+
+/// A `DkgMessage` is sent from one `DkgParticipant` to the others.
+///
+/// Messages with a `recipient` carry a secret share and must be sent to that
+/// participant over a private, authenticated channel. Messages without one
+/// must be broadcast to every other participant.
+#[derive(Debug, Clone)]
+pub struct DkgMessage {
+    sender: u32,
+    recipient: Option<u32>,
+    payload: DkgPayload,
+}
+
+/// The contents of a `DkgMessage`, one kind per round of the protocol.
+#[derive(Debug, Clone)]
+enum DkgPayload {
+    /// The dealer's hiding commitments to its sharing polynomial.
+    PedersenCommitments(PedersenCommitments),
+
+    /// The dealer's share of its secret for the recipient.
+    Share {
+        fragment: PrivateKey,
+        blinding_share: BlindingShare,
+    },
+
+    /// The sender's share from `dealer` was missing or invalid.
+    Complaint { dealer: u32 },
+
+    /// The dealer answers a complaint by revealing the `complainer`'s share.
+    Justification {
+        complainer: u32,
+        fragment: PrivateKey,
+        blinding_share: BlindingShare,
+    },
+
+    /// The dealer's Feldman commitments, which reveal its part of the group key.
+    FeldmanCommitments(FeldmanCommitments),
+
+    /// The sender's share from `dealer` doesn't match the dealer's Feldman
+    /// commitments. The share is revealed as proof.
+    FeldmanComplaint {
+        dealer: u32,
+        fragment: PrivateKey,
+        blinding_share: BlindingShare,
+    },
+
+    /// The sender's share from `dealer`, revealed so that everyone can
+    /// reconstruct the dealer's polynomial.
+    ReconstructionShare {
+        dealer: u32,
+        fragment: PrivateKey,
+        blinding_share: BlindingShare,
+    },
+}
+
+impl DkgMessage {
+    /// Returns the ID of the participant that sent the message.
+    pub fn sender(&self) -> u32 {
+        self.sender
+    }
+
+    /// Returns the ID of the participant the message must privately be sent
+    /// to, or `None` if it must be broadcast to every other participant.
+    pub fn recipient(&self) -> Option<u32> {
+        self.recipient
+    }
+
+    /// Serializes the `DkgMessage` as its sender's and recipient's 4 byte
+    /// big-endian IDs, with a zero recipient for a broadcast, followed by a
+    /// tag byte for the kind of payload and the payload itself.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buff = Vec::<u8>::new();
+        buff.extend_from_slice(&self.sender.to_be_bytes());
+        buff.extend_from_slice(&self.recipient.unwrap_or(0).to_be_bytes());
+        self.payload.to_bytes(&mut buff);
+        buff
+    }
+
+    /// Deserializes from a `&[u8]` to a `DkgMessage`.
+    /// This returns an `Error` if the input is too short, has an unknown
+    /// payload tag, or contains a payload that is not canonical.
+    ///
+    /// The sender and recipient aren't checked against the participants
+    /// here, but by `DkgParticipant::handle`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DkgMessage, Error> {
+        let (sender, bytes) = split_participant_id(bytes)?;
+        let (recipient, bytes) = split_participant_id(bytes)?;
+        Ok(DkgMessage {
+            sender,
+            recipient: if recipient == 0 {
+                None
+            } else {
+                Some(recipient)
+            },
+            payload: DkgPayload::from_bytes(bytes)?,
+        })
+    }
+}
+
+impl DkgPayload {
+    /// Appends the payload to `buff`, as its tag byte followed by its fields
+    /// in order. Shares are serialized as the 32 byte `BlindingShare` followed
+    /// by the `PrivateKey` fragment, so that the fragment comes last.
+    fn to_bytes(&self, buff: &mut Vec<u8>) {
+        match self {
+            DkgPayload::PedersenCommitments(commitments) => {
+                buff.push(PEDERSEN_COMMITMENTS_TAG);
+                buff.extend_from_slice(&commitments.to_bytes());
+            }
+            DkgPayload::Share {
+                fragment,
+                blinding_share,
+            } => {
+                buff.push(SHARE_TAG);
+                share_to_bytes(fragment, blinding_share, buff);
+            }
+            DkgPayload::Complaint { dealer } => {
+                buff.push(COMPLAINT_TAG);
+                buff.extend_from_slice(&dealer.to_be_bytes());
+            }
+            DkgPayload::Justification {
+                complainer,
+                fragment,
+                blinding_share,
+            } => {
+                buff.push(JUSTIFICATION_TAG);
+                buff.extend_from_slice(&complainer.to_be_bytes());
+                share_to_bytes(fragment, blinding_share, buff);
+            }
+            DkgPayload::FeldmanCommitments(commitments) => {
+                buff.push(FELDMAN_COMMITMENTS_TAG);
+                buff.extend_from_slice(&commitments.to_bytes());
+            }
+            DkgPayload::FeldmanComplaint {
+                dealer,
+                fragment,
+                blinding_share,
+            } => {
+                buff.push(FELDMAN_COMPLAINT_TAG);
+                buff.extend_from_slice(&dealer.to_be_bytes());
+                share_to_bytes(fragment, blinding_share, buff);
+            }
+            DkgPayload::ReconstructionShare {
+                dealer,
+                fragment,
+                blinding_share,
+            } => {
+                buff.push(RECONSTRUCTION_SHARE_TAG);
+                buff.extend_from_slice(&dealer.to_be_bytes());
+                share_to_bytes(fragment, blinding_share, buff);
+            }
+        }
+    }
+
+    /// Deserializes a payload serialized with `to_bytes`.
+    fn from_bytes(bytes: &[u8]) -> Result<DkgPayload, Error> {
+        let (tag, bytes) = bytes.split_first().ok_or(Error::InvalidLength(0))?;
+        match *tag {
+            PEDERSEN_COMMITMENTS_TAG => Ok(DkgPayload::PedersenCommitments(
+                PedersenCommitments::from_bytes(bytes)?,
+            )),
+            SHARE_TAG => {
+                let (fragment, blinding_share) = share_from_bytes(bytes)?;
+                Ok(DkgPayload::Share {
+                    fragment,
+                    blinding_share,
+                })
+            }
+            COMPLAINT_TAG => {
+                let (dealer, bytes) = split_participant_id(bytes)?;
+                if !bytes.is_empty() {
+                    return Err(Error::InvalidLength(
+                        PARTICIPANT_ID_BYTES_LENGTH + bytes.len(),
+                    ));
+                }
+                Ok(DkgPayload::Complaint { dealer })
+            }
+            JUSTIFICATION_TAG => {
+                let (complainer, bytes) = split_participant_id(bytes)?;
+                let (fragment, blinding_share) = share_from_bytes(bytes)?;
+                Ok(DkgPayload::Justification {
+                    complainer,
+                    fragment,
+                    blinding_share,
+                })
+            }
+            FELDMAN_COMMITMENTS_TAG => Ok(DkgPayload::FeldmanCommitments(
+                FeldmanCommitments::from_bytes(bytes)?,
+            )),
+            FELDMAN_COMPLAINT_TAG => {
+                let (dealer, bytes) = split_participant_id(bytes)?;
+                let (fragment, blinding_share) = share_from_bytes(bytes)?;
+                Ok(DkgPayload::FeldmanComplaint {
+                    dealer,
+                    fragment,
+                    blinding_share,
+                })
+            }
+            RECONSTRUCTION_SHARE_TAG => {
+                let (dealer, bytes) = split_participant_id(bytes)?;
+                let (fragment, blinding_share) = share_from_bytes(bytes)?;
+                Ok(DkgPayload::ReconstructionShare {
+                    dealer,
+                    fragment,
+                    blinding_share,
+                })
+            }
+            tag => Err(Error::InvalidMessageType(tag)),
+        }
+    }
+}
+
+/// Splits a 4 byte big-endian participant ID off the front of `bytes`.
+fn split_participant_id(bytes: &[u8]) -> Result<(u32, &[u8]), Error> {
+    if bytes.len() < PARTICIPANT_ID_BYTES_LENGTH {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+    let mut id_bytes = [0u8; PARTICIPANT_ID_BYTES_LENGTH];
+    id_bytes.copy_from_slice(&bytes[..PARTICIPANT_ID_BYTES_LENGTH]);
+    Ok((
+        u32::from_be_bytes(id_bytes),
+        &bytes[PARTICIPANT_ID_BYTES_LENGTH..],
+    ))
+}
+
+/// Appends a share to `buff`, as its `BlindingShare` followed by its fragment.
+fn share_to_bytes(fragment: &PrivateKey, blinding_share: &BlindingShare, buff: &mut Vec<u8>) {
+    buff.extend_from_slice(&blinding_share.to_bytes());
+    let start = buff.len();
+    buff.resize(start + fragment.serialized_size(), 0);
+    fragment
+        .to_bytes(&mut buff[start..])
+        .expect("the buffer was sized to fit the fragment");
+}
+
+/// Deserializes a share serialized with `share_to_bytes` from the rest of
+/// `bytes`.
+fn share_from_bytes(bytes: &[u8]) -> Result<(PrivateKey, BlindingShare), Error> {
+    if bytes.len() < SCALAR_BYTES_LENGTH {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+    let blinding_share = BlindingShare::from_bytes(&bytes[..SCALAR_BYTES_LENGTH])?;
+    let fragment = PrivateKey::from_bytes(&bytes[SCALAR_BYTES_LENGTH..])?;
+    Ok((fragment, blinding_share))
+}
+
+/// The rounds of the protocol, named after the step that ends them.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Round {
+    Deal,
+    Complain,
+    Justify,
+    Commit,
+    CheckCommitments,
+    Reconstruct,
+    Finish,
+}
+
+/// The result of a successful distributed key generation for one participant.
+#[derive(Debug, Clone)]
+pub struct DkgOutput {
+    fragment: PrivateKey,
+    public_key: PublicKey,
+    public_key_shares: Vec<PublicKey>,
+    qualified: Vec<u32>,
+}
+
+impl DkgOutput {
+    /// Returns this participant's `PrivateKey` fragment of the group key.
+    /// Its fragment index is the participant's ID.
+    pub fn fragment(&self) -> PrivateKey {
+        self.fragment
+    }
+
+    /// Returns the group `PublicKey`, which verifies `Signature`s assembled
+    /// from a threshold of fragment signatures.
+    pub fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    /// Returns the public key share of the participant with ID `id`, which is
+    /// the `PublicKey` of that participant's fragment. It verifies the
+    /// participant's signature fragments with `PublicKey::verify_fragment`.
+    pub fn public_key_share(&self, id: u32) -> Option<PublicKey> {
+        self.public_key_shares
+            .get((id as usize).wrapping_sub(1))
+            .copied()
+    }
+
+    /// Returns the IDs of the dealers whose secrets make up the group key.
+    pub fn qualified(&self) -> &[u32] {
+        &self.qualified[..]
+    }
+}
+
+/// A `DkgParticipant` runs one participant's side of the distributed key
+/// generation (DKG) protocol of Gennaro, Jarecki, Krawczyk and Rabin, so that
+/// `n` participants can generate a threshold key without anyone ever
+/// knowing the full `PrivateKey`.
+///
+/// Each participant deals a Pedersen VSS of a random secret to the others,
+/// and its fragment of the group key is the sum of the shares it receives.
+/// Dealers that misbehave are either disqualified, or have their secret
+/// reconstructed publicly by the honest participants, who all end up with
+/// the same group `PublicKey`.
+///
+/// The protocol is a message-driven state machine. Participants are numbered
+/// from `1` to `n`, and every participant runs each step below in turn,
+/// delivering the returned messages to the others (see `DkgMessage`) and
+/// passing the messages it receives to `handle` before running the next step:
+///  1. `deal` -- commits to a sharing polynomial and sends out shares.
+///  2. `complain` -- complains about dealers that sent an invalid share.
+///  3. `justify` -- answers complaints by revealing the disputed shares.
+///  4. `commit` -- reveals Feldman commitments to the qualified secrets.
+///  5. `check_commitments` -- complains about invalid Feldman commitments.
+///  6. `reconstruct` -- reveals shares of dealers with invalid commitments.
+///
+/// Finally, `finish` returns this participant's `DkgOutput`.
+///
+/// Note: This assumes a synchronous network; a message that hasn't arrived
+/// by the end of its round is treated as missing.
+#[derive(Debug, Clone)]
+pub struct DkgParticipant {
+    id: u32,
+    threshold: usize,
+    participants: u32,
+    round: Round,
+    coeffs: Vec<Scalar>,
+    blinding_coeffs: Vec<Scalar>,
+    pedersen_commitments: BTreeMap<u32, PedersenCommitments>,
+    shares: BTreeMap<u32, (Scalar, BlindingShare)>,
+    complaints: BTreeMap<u32, BTreeSet<u32>>,
+    justified: BTreeMap<u32, BTreeSet<u32>>,
+    disqualified: BTreeSet<u32>,
+    qualified: BTreeSet<u32>,
+    feldman_commitments: BTreeMap<u32, FeldmanCommitments>,
+    reconstructing: BTreeSet<u32>,
+    reconstruction_shares: BTreeMap<u32, BTreeMap<u32, Scalar>>,
+}
+
+impl DkgParticipant {
+    /// Creates the participant with ID `id` in a DKG between `participants`
+    /// participants, for a group key with a threshold of `threshold`.
+    ///
+    /// Returns `Error::InvalidParticipant` if `id` is not between `1` and
+    /// `participants`, and `Error::NotEnoughParticipants` if the threshold
+    /// is zero or larger than the number of participants.
+    pub fn new(id: u32, threshold: usize, participants: u32) -> Result<DkgParticipant, Error> {
+        if threshold == 0 || threshold > participants as usize {
+            return Err(Error::NotEnoughParticipants);
+        }
+        if id == 0 || id > participants {
+            return Err(Error::InvalidParticipant(id));
+        }
+        Ok(DkgParticipant {
+            id,
+            threshold,
+            participants,
+            round: Round::Deal,
+            coeffs: Vec::new(),
+            blinding_coeffs: Vec::new(),
+            pedersen_commitments: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            complaints: BTreeMap::new(),
+            justified: BTreeMap::new(),
+            disqualified: BTreeSet::new(),
+            qualified: BTreeSet::new(),
+            feldman_commitments: BTreeMap::new(),
+            reconstructing: BTreeSet::new(),
+            reconstruction_shares: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the ID of this participant.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Round 1: Generates a random secret and deals it to the other
+    /// participants with a Pedersen VSS.
+    ///
+    /// Returns the broadcast commitments and a private share for each of the
+    /// other participants.
+    pub fn deal(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::Deal, Round::Complain)?;
+
+        self.coeffs = random_poly(&PrivateKey::random().0, self.threshold);
+        self.blinding_coeffs = random_poly(&PrivateKey::random().0, self.threshold);
+        let commitments = PedersenCommitments::new(&self.coeffs[..], &self.blinding_coeffs[..]);
+        self.pedersen_commitments
+            .insert(self.id, commitments.clone());
+        self.shares.insert(self.id, self.share_for(self.id));
+
+        let mut messages = vec![self.broadcast(DkgPayload::PedersenCommitments(commitments))];
+        for recipient in self.others() {
+            let (value, blinding_share) = self.share_for(recipient);
+            messages.push(DkgMessage {
+                sender: self.id,
+                recipient: Some(recipient),
+                payload: DkgPayload::Share {
+                    fragment: PrivateKey(value, Some(fragment_index(recipient))),
+                    blinding_share,
+                },
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Round 2: Checks the shares received from every dealer, and complains
+    /// about each dealer whose share is missing or invalid.
+    ///
+    /// Dealers that didn't broadcast valid commitments are disqualified.
+    pub fn complain(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::Complain, Round::Justify)?;
+
+        let mut messages = Vec::<DkgMessage>::new();
+        for dealer in self.others() {
+            let commitments = match self.pedersen_commitments.get(&dealer) {
+                Some(commitments) if commitments.threshold() == self.threshold => commitments,
+                _ => {
+                    self.disqualified.insert(dealer);
+                    continue;
+                }
+            };
+            let valid = match self.shares.get(&dealer) {
+                Some((value, blinding_share)) => {
+                    commitments.verify(value, blinding_share, &fragment_index(self.id))
+                        == VerificationResult::Valid
+                }
+                None => false,
+            };
+            if !valid {
+                self.complaints.entry(dealer).or_default().insert(self.id);
+                messages.push(self.broadcast(DkgPayload::Complaint { dealer }));
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Round 3: Answers every complaint against this participant by
+    /// revealing the complainer's share.
+    pub fn justify(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::Justify, Round::Commit)?;
+
+        let complainers = self.complaints.get(&self.id).cloned().unwrap_or_default();
+        let mut messages = Vec::<DkgMessage>::new();
+        for complainer in complainers {
+            let (value, blinding_share) = self.share_for(complainer);
+            self.justified
+                .entry(self.id)
+                .or_default()
+                .insert(complainer);
+            messages.push(self.broadcast(DkgPayload::Justification {
+                complainer,
+                fragment: PrivateKey(value, Some(fragment_index(complainer))),
+                blinding_share,
+            }));
+        }
+        Ok(messages)
+    }
+
+    /// Round 4: Determines the qualified dealers, and if this participant is
+    /// one of them, broadcasts Feldman commitments to its polynomial.
+    ///
+    /// A dealer is disqualified if it left a complaint unanswered, answered
+    /// one with an invalid share, or received `threshold` or more complaints.
+    /// Returns `Error::NotEnoughParticipants` if every dealer is disqualified.
+    pub fn commit(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::Commit, Round::CheckCommitments)?;
+
+        let no_complaints = BTreeSet::new();
+        for dealer in 1..=self.participants {
+            let complaints = self.complaints.get(&dealer).unwrap_or(&no_complaints);
+            let justified = self.justified.get(&dealer).unwrap_or(&no_complaints);
+            if !self.disqualified.contains(&dealer)
+                && complaints.len() < self.threshold
+                && complaints.is_subset(justified)
+            {
+                self.qualified.insert(dealer);
+            }
+        }
+        if self.qualified.is_empty() {
+            return Err(Error::NotEnoughParticipants);
+        }
+
+        if !self.qualified.contains(&self.id) {
+            return Ok(Vec::new());
+        }
+        let commitments = FeldmanCommitments::new(&self.coeffs[..]);
+        self.feldman_commitments
+            .insert(self.id, commitments.clone());
+        Ok(vec![
+            self.broadcast(DkgPayload::FeldmanCommitments(commitments))
+        ])
+    }
+
+    /// Round 5: Checks the share from every qualified dealer against its
+    /// Feldman commitments, and complains about each dealer whose commitments
+    /// are missing or don't match.
+    ///
+    /// Returns `Error::InvalidParticipant` if this participant has no share
+    /// from a qualified dealer.
+    pub fn check_commitments(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::CheckCommitments, Round::Reconstruct)?;
+
+        let mut messages = Vec::<DkgMessage>::new();
+        for dealer in self.qualified.clone() {
+            if dealer == self.id {
+                continue;
+            }
+            let (value, blinding_share) = self.qualified_share(dealer)?;
+            let valid = match self.feldman_commitments.get(&dealer) {
+                Some(commitments) if commitments.threshold() == self.threshold => {
+                    commitments.verify(&value, &fragment_index(self.id))
+                        == VerificationResult::Valid
+                }
+                _ => false,
+            };
+            if !valid {
+                self.reconstructing.insert(dealer);
+                messages.push(self.broadcast(DkgPayload::FeldmanComplaint {
+                    dealer,
+                    fragment: PrivateKey(value, Some(fragment_index(self.id))),
+                    blinding_share,
+                }));
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Round 6: Reveals this participant's share from every dealer with
+    /// invalid Feldman commitments, so that their polynomials can be
+    /// reconstructed.
+    ///
+    /// Returns `Error::InvalidParticipant` if this participant has no share
+    /// from a dealer being reconstructed.
+    pub fn reconstruct(&mut self) -> Result<Vec<DkgMessage>, Error> {
+        self.advance(Round::Reconstruct, Round::Finish)?;
+
+        let mut messages = Vec::<DkgMessage>::new();
+        for dealer in self.reconstructing.clone() {
+            let (value, blinding_share) = self.qualified_share(dealer)?;
+            self.reconstruction_shares
+                .entry(dealer)
+                .or_default()
+                .insert(self.id, value);
+            messages.push(self.broadcast(DkgPayload::ReconstructionShare {
+                dealer,
+                fragment: PrivateKey(value, Some(fragment_index(self.id))),
+                blinding_share,
+            }));
+        }
+        Ok(messages)
+    }
+
+    /// Returns this participant's `DkgOutput` once every round is done.
+    ///
+    /// Returns `Error::NotEnoughParticipants` if fewer than `threshold`
+    /// participants revealed valid shares of a dealer being reconstructed,
+    /// and `Error::InvalidParticipant` if this participant has no share from
+    /// a qualified dealer.
+    pub fn finish(&self) -> Result<DkgOutput, Error> {
+        if self.round != Round::Finish {
+            return Err(Error::WrongRound);
+        }
+
+        // Each qualified dealer contributes its polynomial "in the exponent",
+        // either from its Feldman commitments or by interpolating the shares
+        // revealed during reconstruction. A dealer without commitments is
+        // reconstructed like one with invalid commitments.
+        let mut committed = Vec::<&FeldmanCommitments>::new();
+        let mut reconstructed = Vec::<Vec<(Scalar, Scalar)>>::new();
+        for dealer in self.qualified.iter() {
+            match self.feldman_commitments.get(dealer) {
+                Some(commitments) if !self.reconstructing.contains(dealer) => {
+                    committed.push(commitments)
+                }
+                _ => {
+                    let points = self
+                        .reconstruction_shares
+                        .get(dealer)
+                        .map(|shares| {
+                            shares
+                                .iter()
+                                .take(self.threshold)
+                                .map(|(holder, value)| (fragment_index(*holder), *value))
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    if points.len() < self.threshold {
+                        return Err(Error::NotEnoughParticipants);
+                    }
+                    reconstructed.push(points);
+                }
+            }
+        }
+        let evaluate = |x: &Scalar| {
+            let from_commitments = committed
+                .iter()
+                .fold(G1Projective::identity(), |acc, commitments| {
+                    acc + commitments.evaluate(x)
+                });
+            reconstructed.iter().fold(from_commitments, |acc, points| {
+                acc + G1Affine::generator() * interpolate(&points[..], x)
+            })
+        };
+
+        let mut fragment = Scalar::zero();
+        for dealer in self.qualified.iter() {
+            fragment += self.qualified_share(*dealer)?.0;
+        }
+        Ok(DkgOutput {
+            fragment: PrivateKey(fragment, Some(fragment_index(self.id))),
+            public_key: PublicKey(evaluate(&Scalar::zero()).into(), None),
+            public_key_shares: (1..=self.participants)
+                .map(|id| {
+                    let index = fragment_index(id);
+                    PublicKey(evaluate(&index).into(), Some(index))
+                })
+                .collect(),
+            qualified: self.qualified.iter().copied().collect(),
+        })
+    }
+
+    /// Handles a `message` received from another participant.
+    ///
+    /// Messages must be handled in the round they were sent, i.e. before this
+    /// participant runs its next step; otherwise this returns
+    /// `Error::WrongRound`. Messages that show their sender misbehaved are
+    /// recorded, rather than returned as an `Error`.
+    pub fn handle(&mut self, message: &DkgMessage) -> Result<(), Error> {
+        let sender = message.sender;
+        if sender == self.id || !self.is_participant(sender) {
+            return Err(Error::InvalidParticipant(sender));
+        }
+        if let Some(recipient) = message.recipient {
+            if recipient != self.id {
+                return Err(Error::InvalidParticipant(recipient));
+            }
+        }
+
+        match (self.round, &message.payload) {
+            (Round::Complain, DkgPayload::PedersenCommitments(commitments)) => {
+                if self.pedersen_commitments.contains_key(&sender) {
+                    return Err(Error::DuplicateMessage(sender));
+                }
+                self.pedersen_commitments
+                    .insert(sender, commitments.clone());
+            }
+            (
+                Round::Complain,
+                DkgPayload::Share {
+                    fragment,
+                    blinding_share,
+                },
+            ) => {
+                if self.shares.contains_key(&sender) {
+                    return Err(Error::DuplicateMessage(sender));
+                }
+                // A share for the wrong index is as good as an invalid one.
+                if fragment.1 == Some(fragment_index(self.id)) {
+                    self.shares.insert(sender, (fragment.0, *blinding_share));
+                }
+            }
+            (Round::Justify, DkgPayload::Complaint { dealer }) => {
+                if !self.is_participant(*dealer) {
+                    return Err(Error::InvalidParticipant(*dealer));
+                }
+                if !self.complaints.entry(*dealer).or_default().insert(sender) {
+                    return Err(Error::DuplicateMessage(sender));
+                }
+            }
+            (
+                Round::Commit,
+                DkgPayload::Justification {
+                    complainer,
+                    fragment,
+                    blinding_share,
+                },
+            ) => {
+                if !self.is_participant(*complainer) {
+                    return Err(Error::InvalidParticipant(*complainer));
+                }
+                if !self
+                    .justified
+                    .entry(sender)
+                    .or_default()
+                    .insert(*complainer)
+                {
+                    return Err(Error::DuplicateMessage(sender));
+                }
+                if self.verify_pedersen_share(sender, *complainer, fragment, blinding_share) {
+                    if *complainer == self.id {
+                        self.shares.insert(sender, (fragment.0, *blinding_share));
+                    }
+                } else {
+                    self.disqualified.insert(sender);
+                }
+            }
+            (Round::CheckCommitments, DkgPayload::FeldmanCommitments(commitments)) => {
+                if self.feldman_commitments.contains_key(&sender) {
+                    return Err(Error::DuplicateMessage(sender));
+                }
+                if self.qualified.contains(&sender) {
+                    self.feldman_commitments.insert(sender, commitments.clone());
+                }
+            }
+            (
+                Round::Reconstruct,
+                DkgPayload::FeldmanComplaint {
+                    dealer,
+                    fragment,
+                    blinding_share,
+                },
+            ) => {
+                if !self.is_participant(*dealer) {
+                    return Err(Error::InvalidParticipant(*dealer));
+                }
+                // The complaint only counts if the revealed share is valid for
+                // the Pedersen commitments, but not the Feldman commitments.
+                if self.qualified.contains(dealer)
+                    && self.verify_pedersen_share(*dealer, sender, fragment, blinding_share)
+                {
+                    let valid = match self.feldman_commitments.get(dealer) {
+                        Some(commitments) if commitments.threshold() == self.threshold => {
+                            commitments.verify(&fragment.0, &fragment_index(sender))
+                                == VerificationResult::Valid
+                        }
+                        _ => false,
+                    };
+                    if !valid {
+                        self.reconstructing.insert(*dealer);
+                    }
+                }
+            }
+            (
+                Round::Finish,
+                DkgPayload::ReconstructionShare {
+                    dealer,
+                    fragment,
+                    blinding_share,
+                },
+            ) => {
+                if !self.is_participant(*dealer) {
+                    return Err(Error::InvalidParticipant(*dealer));
+                }
+                if self.reconstructing.contains(dealer)
+                    && self.verify_pedersen_share(*dealer, sender, fragment, blinding_share)
+                {
+                    let shares = self.reconstruction_shares.entry(*dealer).or_default();
+                    if shares.insert(sender, fragment.0).is_some() {
+                        return Err(Error::DuplicateMessage(sender));
+                    }
+                }
+            }
+            _ => return Err(Error::WrongRound),
+        }
+        Ok(())
+    }
+
+    /// Moves from the `from` round to the `to` round, or returns
+    /// `Error::WrongRound` if this participant isn't in the `from` round.
+    fn advance(&mut self, from: Round, to: Round) -> Result<(), Error> {
+        if self.round != from {
+            return Err(Error::WrongRound);
+        }
+        self.round = to;
+        Ok(())
+    }
+
+    /// Returns this participant's share from the qualified `dealer`.
+    ///
+    /// Every qualified dealer either sent a valid share or revealed it in a
+    /// justification, so a missing share returns `Error::InvalidParticipant`
+    /// rather than being trusted to never happen.
+    fn qualified_share(&self, dealer: u32) -> Result<(Scalar, BlindingShare), Error> {
+        self.shares
+            .get(&dealer)
+            .copied()
+            .ok_or(Error::InvalidParticipant(dealer))
+    }
+
+    /// Returns this participant's share of its own secret for `recipient`.
+    fn share_for(&self, recipient: u32) -> (Scalar, BlindingShare) {
+        let index = fragment_index(recipient);
+        (
+            poly_eval(&self.coeffs[..], &index),
+            BlindingShare(poly_eval(&self.blinding_coeffs[..], &index)),
+        )
+    }
+
+    /// Checks that `fragment` is `dealer`'s valid share for `holder`.
+    fn verify_pedersen_share(
+        &self,
+        dealer: u32,
+        holder: u32,
+        fragment: &PrivateKey,
+        blinding_share: &BlindingShare,
+    ) -> bool {
+        let index = fragment_index(holder);
+        fragment.1 == Some(index)
+            && match self.pedersen_commitments.get(&dealer) {
+                Some(commitments) => {
+                    commitments.verify(&fragment.0, blinding_share, &index)
+                        == VerificationResult::Valid
+                }
+                None => false,
+            }
+    }
+
+    fn broadcast(&self, payload: DkgPayload) -> DkgMessage {
+        DkgMessage {
+            sender: self.id,
+            recipient: None,
+            payload,
+        }
+    }
+
+    fn others(&self) -> impl Iterator<Item = u32> {
+        let id = self.id;
+        (1..=self.participants).filter(move |other| *other != id)
+    }
+
+    fn is_participant(&self, id: u32) -> bool {
+        id >= 1 && id <= self.participants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::Signature;
+    use crate::traits::{ThresholdKey, ThresholdSignature};
+
+    type Step = fn(&mut DkgParticipant) -> Result<Vec<DkgMessage>, Error>;
+
+    /// Runs the first `steps` steps of a DKG between `n` participants
+    /// in-process, passing every message through `tamper`, which can modify it
+    /// or drop it by returning `false`.
+    fn run_steps<F>(threshold: usize, n: u32, steps: usize, mut tamper: F) -> Vec<DkgParticipant>
+    where
+        F: FnMut(&mut DkgMessage) -> bool,
+    {
+        let mut participants = (1..=n)
+            .map(|id| DkgParticipant::new(id, threshold, n).unwrap())
+            .collect::<Vec<_>>();
+        let all_steps: [Step; 6] = [
+            DkgParticipant::deal,
+            DkgParticipant::complain,
+            DkgParticipant::justify,
+            DkgParticipant::commit,
+            DkgParticipant::check_commitments,
+            DkgParticipant::reconstruct,
+        ];
+        for step in all_steps[..steps].iter() {
+            let mut messages = Vec::<DkgMessage>::new();
+            for participant in participants.iter_mut() {
+                messages.extend(step(participant).unwrap());
+            }
+            for mut message in messages {
+                if !tamper(&mut message) {
+                    continue;
+                }
+                for participant in participants.iter_mut() {
+                    let is_recipient = match message.recipient() {
+                        Some(recipient) => recipient == participant.id(),
+                        None => message.sender() != participant.id(),
+                    };
+                    if is_recipient {
+                        participant.handle(&message).unwrap();
+                    }
+                }
+            }
+        }
+        participants
+    }
+
+    /// Runs a whole DKG between `n` participants in-process, as in
+    /// `run_steps`, and returns their outputs.
+    fn run_dkg<F>(threshold: usize, n: u32, tamper: F) -> Vec<DkgOutput>
+    where
+        F: FnMut(&mut DkgMessage) -> bool,
+    {
+        run_steps(threshold, n, 6, tamper)
+            .iter()
+            .map(|participant| participant.finish().unwrap())
+            .collect()
+    }
+
+    /// Checks that the `honest` participants agree on the group key, and that
+    /// a threshold of their fragments produce signatures valid under it.
+    fn check_outputs(outputs: &[DkgOutput], honest: &[u32], threshold: usize) {
+        let output = |id: &u32| &outputs[*id as usize - 1];
+        let public_key = output(&honest[0]).public_key();
+        for id in honest.iter() {
+            assert_eq!(output(id).public_key(), public_key);
+            assert_eq!(output(id).qualified(), output(&honest[0]).qualified());
+            for other in honest.iter() {
+                assert_eq!(
+                    output(id).public_key_share(*other),
+                    Some(output(other).fragment().public_key())
+                );
+            }
+        }
+
+        let fragments = honest
+            .iter()
+            .map(|id| output(id).fragment())
+            .collect::<Vec<_>>();
+        let recovered = PrivateKey::recover(&fragments[fragments.len() - threshold..]).unwrap();
+        assert_eq!(recovered.public_key(), public_key);
+
+        let sigs = fragments[..threshold]
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            public_key.verify_message(b"NuBLS!", &Signature::assemble(&sigs[..]).unwrap()),
+            VerificationResult::Valid
+        );
+    }
+
+    #[test]
+    fn test_honest_dkg() {
+        let outputs = run_dkg(3, 5, |_| true);
+        check_outputs(&outputs[..], &[1, 2, 3, 4, 5], 3);
+        assert_eq!(outputs[0].qualified(), &[1, 2, 3, 4, 5]);
+        assert_eq!(outputs[0].public_key_share(0), None);
+        assert_eq!(outputs[0].public_key_share(6), None);
+    }
+
+    #[test]
+    fn test_complaint_is_justified() {
+        // Dealer 2 sends participant 3 a bad share, but answers the complaint.
+        let outputs = run_dkg(3, 5, |message| {
+            if let (2, Some(3), DkgPayload::Share { fragment, .. }) =
+                (message.sender, message.recipient, &mut message.payload)
+            {
+                fragment.0 += Scalar::one();
+            }
+            true
+        });
+        check_outputs(&outputs[..], &[1, 2, 3, 4, 5], 3);
+        assert_eq!(outputs[0].qualified(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_unanswered_complaint_disqualifies_dealer() {
+        // Dealer 2 sends participant 3 a bad share and ignores the complaint.
+        let outputs = run_dkg(3, 5, |message| {
+            match (&mut message.payload, message.sender) {
+                (DkgPayload::Share { fragment, .. }, 2) if message.recipient == Some(3) => {
+                    fragment.0 += Scalar::one();
+                    true
+                }
+                (DkgPayload::Justification { .. }, 2) => false,
+                _ => true,
+            }
+        });
+        check_outputs(&outputs[..], &[1, 3, 4, 5], 3);
+        assert_eq!(outputs[0].qualified(), &[1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_invalid_feldman_commitments_are_reconstructed() {
+        // Dealer 4 broadcasts Feldman commitments to the wrong polynomial.
+        let outputs = run_dkg(3, 5, |message| {
+            if let (4, DkgPayload::FeldmanCommitments(commitments)) =
+                (message.sender, &mut message.payload)
+            {
+                let coeffs = random_poly(&PrivateKey::random().0, 3);
+                *commitments = FeldmanCommitments::new(&coeffs[..]);
+            }
+            true
+        });
+        check_outputs(&outputs[..], &[1, 2, 3, 4, 5], 3);
+        assert_eq!(outputs[0].qualified(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        assert_eq!(
+            DkgParticipant::new(0, 3, 5).unwrap_err(),
+            Error::InvalidParticipant(0)
+        );
+        assert_eq!(
+            DkgParticipant::new(1, 6, 5).unwrap_err(),
+            Error::NotEnoughParticipants
+        );
+
+        let mut alice = DkgParticipant::new(1, 2, 3).unwrap();
+        let mut bob = DkgParticipant::new(2, 2, 3).unwrap();
+        assert_eq!(alice.complain().unwrap_err(), Error::WrongRound);
+        assert_eq!(alice.finish().unwrap_err(), Error::WrongRound);
+
+        let messages = bob.deal().unwrap();
+        assert_eq!(bob.deal().unwrap_err(), Error::WrongRound);
+
+        // Alice hasn't dealt yet, so she's not expecting Bob's messages.
+        assert_eq!(alice.handle(&messages[0]).unwrap_err(), Error::WrongRound);
+        alice.deal().unwrap();
+        alice.handle(&messages[0]).unwrap();
+        assert_eq!(
+            alice.handle(&messages[0]).unwrap_err(),
+            Error::DuplicateMessage(2)
+        );
+
+        // Bob's share for Carol isn't for Alice.
+        let for_carol = messages
+            .iter()
+            .find(|message| message.recipient() == Some(3))
+            .unwrap();
+        assert_eq!(
+            alice.handle(for_carol).unwrap_err(),
+            Error::InvalidParticipant(3)
+        );
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        // Dealer 2 sends participant 3 a bad share and answers the complaint,
+        // and dealer 4 broadcasts invalid Feldman commitments, so that every
+        // kind of message is sent. Every message goes through serialization.
+        let mut tags = BTreeSet::<u8>::new();
+        let outputs = run_dkg(3, 5, |message| {
+            match (message.sender, &mut message.payload) {
+                (2, DkgPayload::Share { fragment, .. }) if message.recipient == Some(3) => {
+                    fragment.0 += Scalar::one();
+                }
+                (4, DkgPayload::FeldmanCommitments(commitments)) => {
+                    let coeffs = random_poly(&PrivateKey::random().0, 3);
+                    *commitments = FeldmanCommitments::new(&coeffs[..]);
+                }
+                _ => (),
+            }
+            let bytes = message.to_bytes();
+            tags.insert(bytes[2 * PARTICIPANT_ID_BYTES_LENGTH]);
+            let deserialized = DkgMessage::from_bytes(&bytes).unwrap();
+            assert_eq!(deserialized.sender(), message.sender());
+            assert_eq!(deserialized.recipient(), message.recipient());
+            assert_eq!(deserialized.to_bytes(), bytes);
+            *message = deserialized;
+            true
+        });
+        check_outputs(&outputs[..], &[1, 2, 3, 4, 5], 3);
+        assert_eq!(
+            tags.into_iter().collect::<Vec<_>>(),
+            (1..=7).collect::<Vec<_>>()
+        );
+
+        let complaint = DkgMessage {
+            sender: 3,
+            recipient: None,
+            payload: DkgPayload::Complaint { dealer: 2 },
+        };
+        let bytes = complaint.to_bytes();
+        assert_eq!(bytes, [0, 0, 0, 3, 0, 0, 0, 0, COMPLAINT_TAG, 0, 0, 0, 2]);
+        assert_eq!(
+            DkgMessage::from_bytes(&bytes[..6]).unwrap_err(),
+            Error::InvalidLength(2)
+        );
+        assert_eq!(
+            DkgMessage::from_bytes(&bytes[..8]).unwrap_err(),
+            Error::InvalidLength(0)
+        );
+        assert_eq!(
+            DkgMessage::from_bytes(&bytes[..12]).unwrap_err(),
+            Error::InvalidLength(3)
+        );
+        assert_eq!(
+            DkgMessage::from_bytes(&[&bytes[..], &[0u8]].concat()).unwrap_err(),
+            Error::InvalidLength(5)
+        );
+        let mut unknown = bytes.clone();
+        unknown[8] = 0x08;
+        assert_eq!(
+            DkgMessage::from_bytes(&unknown).unwrap_err(),
+            Error::InvalidMessageType(0x08)
+        );
+    }
+
+    #[test]
+    fn test_missing_state_is_an_error() {
+        // Runs the first `steps` steps of an honest DKG between 3
+        // participants, and returns the first participant.
+        let run_until = |steps| run_steps(2, 3, steps, |_| true).remove(0);
+
+        // A qualified dealer's share or commitments going missing returns an
+        // `Error` in every step that needs them, rather than panicking.
+        let mut alice = run_until(4);
+        alice.shares.remove(&2);
+        assert_eq!(
+            alice.check_commitments().unwrap_err(),
+            Error::InvalidParticipant(2)
+        );
+
+        let mut alice = run_until(5);
+        alice.reconstructing.insert(2);
+        alice.shares.remove(&2);
+        assert_eq!(
+            alice.reconstruct().unwrap_err(),
+            Error::InvalidParticipant(2)
+        );
+
+        let mut alice = run_until(6);
+        alice.shares.remove(&3);
+        assert_eq!(alice.finish().unwrap_err(), Error::InvalidParticipant(3));
+
+        // Missing commitments can't be reconstructed without revealed shares.
+        let mut alice = run_until(6);
+        alice.feldman_commitments.remove(&3);
+        assert_eq!(alice.finish().unwrap_err(), Error::NotEnoughParticipants);
+    }
+}
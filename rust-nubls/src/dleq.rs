@@ -0,0 +1,267 @@
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use sha2::{Digest, Sha512};
+
+use crate::bls::VerificationResult;
+use crate::error::Error;
+use crate::keys::{PrivateKey, PublicKey, SCALAR_BYTES_LENGTH};
+use crate::prs::ResigningKey;
+use crate::utils::scalar_from_bytes;
+
+/// The domain separation tag that the challenges of `ResigningKeyProof`s are
+/// hashed under.
+const RESIGNING_KEY_PROOF_DST: &[u8] = b"NUBLS_PRS_RESIGNING_KEY_DLEQ_V1_";
+
+/// A `ResigningKeyProof` is a non-interactive zero-knowledge proof that a
+/// re-signing key is well-formed, i.e. that it maps Bob's designated public
+/// key to Alice's `PublicKey`.
+///
+/// It is a Chaum-Pedersen proof of discrete log equality over G_1, made
+/// non-interactive with the Fiat-Shamir transform: for a re-signing key
+/// $x$ with public key $R = x \cdot G$, and Bob's designated public key $D$,
+/// it proves that Alice's public key is $A = x \cdot D$ without revealing $x$.
+///
+/// Alice publishes it alongside the re-signing key with
+/// `ResigningKey::prove_well_formed`, and a proxy checks it with
+/// `ResigningKey::verify_proof` before accepting the delegation. Anyone else
+/// can check it from public data alone, with the re-signing key's `PublicKey`
+/// and `verify`.
+///
+/// The proof only shows that the key maps $D$ to $A$, so it's only evidence
+/// of a well-formed key if $D$ is Bob's designated public key, obtained from
+/// Bob himself (e.g. from `PRSKey::designated_public_key`) or from a channel
+/// that he authenticates. Never verify it against the $D$ that
+/// `PublicKey::designated_public_key` derives from the re-signing key itself,
+/// as every key, well-formed or not, maps that $D$ to $A$.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ResigningKeyProof(pub(crate) Scalar, pub(crate) Scalar);
+
+impl ResigningKeyProof {
+    /// Proves that the re-signing key $x$ maps the `designated_pubkey` $D$ to
+    /// $x \cdot D$, which is Alice's `PublicKey` for a well-formed key.
+    pub(crate) fn new(resigning_key: &Scalar, designated_pubkey: &PublicKey) -> ResigningKeyProof {
+        let resigning_pubkey = G1Affine::from(G1Affine::generator() * resigning_key);
+        let alice_pubkey = G1Affine::from(designated_pubkey.0 * resigning_key);
+
+        let nonce = PrivateKey::random().0;
+        let commitment_g = G1Affine::from(G1Affine::generator() * nonce);
+        let commitment_d = G1Affine::from(designated_pubkey.0 * nonce);
+        let challenge = challenge(
+            &resigning_pubkey,
+            &designated_pubkey.0,
+            &alice_pubkey,
+            &commitment_g,
+            &commitment_d,
+        );
+        ResigningKeyProof(challenge, nonce + challenge * resigning_key)
+    }
+
+    /// Verifies the proof that the re-signing key with the `resigning_pubkey`
+    /// $R$ maps the `designated_pubkey` $D$ to the `alice_pubkey` $A$.
+    ///
+    /// This only needs public data, so a third party can verify the proof
+    /// without the re-signing key. The `designated_pubkey` must come from Bob,
+    /// not from the re-signing key (see `ResigningKeyProof`).
+    ///
+    /// The commitments are recovered as $z \cdot G - c \cdot R$ and
+    /// $z \cdot D - c \cdot A$, and the proof is `VerificationResult::Valid`
+    /// if they hash back to the challenge $c$.
+    pub fn verify(
+        &self,
+        resigning_pubkey: &PublicKey,
+        designated_pubkey: &PublicKey,
+        alice_pubkey: &PublicKey,
+    ) -> VerificationResult {
+        let ResigningKeyProof(challenge_c, response) = self;
+        let commitment_g =
+            G1Affine::from(G1Projective::generator() * response - resigning_pubkey.0 * challenge_c);
+        let commitment_d =
+            G1Affine::from(designated_pubkey.0 * response - alice_pubkey.0 * challenge_c);
+        let expected = challenge(
+            &resigning_pubkey.0,
+            &designated_pubkey.0,
+            &alice_pubkey.0,
+            &commitment_g,
+            &commitment_d,
+        );
+        if expected == *challenge_c {
+            VerificationResult::Valid
+        } else {
+            VerificationResult::Invalid
+        }
+    }
+
+    /// Serializes the `ResigningKeyProof` to an array of 64 bytes, as its
+    /// challenge followed by its response.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut buff = [0u8; 64];
+        buff[..SCALAR_BYTES_LENGTH].copy_from_slice(&self.0.to_bytes());
+        buff[SCALAR_BYTES_LENGTH..].copy_from_slice(&self.1.to_bytes());
+        buff
+    }
+
+    /// Deserializes from a `&[u8]` to a `ResigningKeyProof`.
+    /// This returns an `Error` if the input is not 64 bytes long, or is not
+    /// canonical.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ResigningKeyProof, Error> {
+        if bytes.len() != 2 * SCALAR_BYTES_LENGTH {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(ResigningKeyProof(
+            scalar_from_bytes(&bytes[..SCALAR_BYTES_LENGTH])?,
+            scalar_from_bytes(&bytes[SCALAR_BYTES_LENGTH..])?,
+        ))
+    }
+}
+
+impl ResigningKey {
+    /// Proves that this re-signing key maps Bob's `designated_pubkey` to
+    /// Alice's `PublicKey`, for Alice to publish alongside the key.
+    ///
+    /// Returns `Error::InvalidFragmentIndex` if this is a fragment, as a
+    /// fragment alone doesn't map to Alice's key.
+    pub fn prove_well_formed(
+        &self,
+        designated_pubkey: &PublicKey,
+    ) -> Result<ResigningKeyProof, Error> {
+        if self.1.is_some() {
+            return Err(Error::InvalidFragmentIndex);
+        }
+        Ok(ResigningKeyProof::new(&self.0, designated_pubkey))
+    }
+
+    /// Verifies the `proof` that this re-signing key maps Bob's
+    /// `designated_pubkey` to the `alice_pubkey`, as a proxy should before
+    /// accepting the delegation.
+    ///
+    /// The `designated_pubkey` must come from Bob, not from
+    /// `PublicKey::designated_public_key` with this key.
+    pub fn verify_proof(
+        &self,
+        proof: &ResigningKeyProof,
+        designated_pubkey: &PublicKey,
+        alice_pubkey: &PublicKey,
+    ) -> VerificationResult {
+        proof.verify(&self.public_key(), designated_pubkey, alice_pubkey)
+    }
+}
+
+/// Hashes the statement and commitments of a `ResigningKeyProof` to its
+/// challenge.
+fn challenge(
+    resigning_pubkey: &G1Affine,
+    designated_pubkey: &G1Affine,
+    alice_pubkey: &G1Affine,
+    commitment_g: &G1Affine,
+    commitment_d: &G1Affine,
+) -> Scalar {
+    let digest = Sha512::new()
+        .chain(RESIGNING_KEY_PROOF_DST)
+        .chain(resigning_pubkey.to_compressed())
+        .chain(designated_pubkey.to_compressed())
+        .chain(alice_pubkey.to_compressed())
+        .chain(commitment_g.to_compressed())
+        .chain(commitment_d.to_compressed())
+        .finalize();
+    let mut scalar_bytes = [0u8; 64];
+    scalar_bytes.copy_from_slice(&digest);
+    Scalar::from_bytes_wide(&scalar_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{PRSKey, ThresholdKey};
+
+    #[test]
+    fn test_resigning_key_proof() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        // Alice proves her re-signing key maps Bob's designated key to hers.
+        let rekey_ab = priv_alice.resigning_key(&pub_bob);
+        let pub_designated_bob = priv_bob.designated_public_key(&pub_alice);
+        let proof = rekey_ab.prove_well_formed(&pub_designated_bob).unwrap();
+        assert_eq!(
+            rekey_ab.verify_proof(&proof, &pub_designated_bob, &pub_alice),
+            VerificationResult::Valid
+        );
+        assert_eq!(
+            proof.verify(&rekey_ab.public_key(), &pub_designated_bob, &pub_alice),
+            VerificationResult::Valid
+        );
+
+        // The proof doesn't hold for other keys.
+        let priv_carol = PrivateKey::random();
+        let pub_carol = priv_carol.public_key();
+        assert_eq!(
+            rekey_ab.verify_proof(&proof, &pub_designated_bob, &pub_carol),
+            VerificationResult::Invalid
+        );
+        let pub_designated_carol = priv_carol.designated_public_key(&pub_alice);
+        assert_eq!(
+            rekey_ab.verify_proof(&proof, &pub_designated_carol, &pub_alice),
+            VerificationResult::Invalid
+        );
+        let rekey_ac = priv_alice.resigning_key(&pub_carol);
+        assert_eq!(
+            rekey_ac.verify_proof(&proof, &pub_designated_bob, &pub_alice),
+            VerificationResult::Invalid
+        );
+
+        // A re-signing key that doesn't map Bob's designated key to Alice's
+        // can't be proven well-formed.
+        let bad_rekey = priv_carol.resigning_key(&pub_bob);
+        let bad_proof = bad_rekey.prove_well_formed(&pub_designated_bob).unwrap();
+        assert_eq!(
+            bad_rekey.verify_proof(&bad_proof, &pub_designated_bob, &pub_alice),
+            VerificationResult::Invalid
+        );
+
+        // The designated public key derived from a re-signing key is no
+        // evidence: even the bad key maps it to Alice's key, so the proof
+        // only means something against the designated key from Bob.
+        let derived_designated = pub_alice.designated_public_key(&bad_rekey).unwrap();
+        assert_ne!(derived_designated, pub_designated_bob);
+        let derived_proof = bad_rekey.prove_well_formed(&derived_designated).unwrap();
+        assert_eq!(
+            derived_proof.verify(&bad_rekey.public_key(), &derived_designated, &pub_alice),
+            VerificationResult::Valid
+        );
+        assert_eq!(
+            derived_proof.verify(&bad_rekey.public_key(), &pub_designated_bob, &pub_alice),
+            VerificationResult::Invalid
+        );
+
+        let tampered = ResigningKeyProof(proof.0, proof.1 + Scalar::one());
+        assert_eq!(
+            rekey_ab.verify_proof(&tampered, &pub_designated_bob, &pub_alice),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            rekey_ab.split(2, 3)[0].prove_well_formed(&pub_designated_bob),
+            Err(Error::InvalidFragmentIndex)
+        );
+    }
+
+    #[test]
+    fn test_resigning_key_proof_serialization() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let rekey_ab = priv_alice.resigning_key(&priv_bob.public_key());
+        let pub_designated_bob = priv_bob.designated_public_key(&priv_alice.public_key());
+        let proof = rekey_ab.prove_well_formed(&pub_designated_bob).unwrap();
+
+        let bytes = proof.to_bytes();
+        assert_eq!(ResigningKeyProof::from_bytes(&bytes), Ok(proof));
+        assert_eq!(
+            ResigningKeyProof::from_bytes(&bytes[..63]),
+            Err(Error::InvalidLength(63))
+        );
+        assert_eq!(
+            ResigningKeyProof::from_bytes(&[0xffu8; 64]),
+            Err(Error::NonCanonicalScalar)
+        );
+    }
+}
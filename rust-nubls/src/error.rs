@@ -0,0 +1,105 @@
+use core::fmt;
+
+/// The errors that can occur when (de)serializing NuBLS types, or when
+/// running an interactive protocol such as distributed key generation.
+///
+/// Deserializers and protocol messages are usually fed untrusted input from
+/// the network, so they return an `Error` rather than panicking.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Error {
+    /// The input or output buffer has the wrong length. Holds the length
+    /// that was given.
+    InvalidLength(usize),
+
+    /// The bytes of a `Scalar` are not the canonical encoding of an element
+    /// below the order of the curve.
+    NonCanonicalScalar,
+
+    /// The bytes are not a valid encoding of a point on the curve.
+    PointNotOnCurve,
+
+    /// The point is on the curve, but not in the prime order subgroup.
+    PointNotInSubgroup,
+
+    /// The point is the identity, which is never a valid key or signature.
+    IdentityPoint,
+
+    /// The fragment index is zero or is not a canonical `Scalar`, or a key or
+    /// signature that should be a fragment isn't one.
+    InvalidFragmentIndex,
+
+    /// Two of the fragments being combined have the same fragment index.
+    DuplicateFragmentIndex,
+
+    /// A protocol message names a participant that isn't part of the
+    /// protocol. Holds the unknown participant ID.
+    InvalidParticipant(u32),
+
+    /// A protocol message or step arrived in the wrong round.
+    WrongRound,
+
+    /// A participant sent the same protocol message twice in a round. Holds
+    /// the ID of the participant.
+    DuplicateMessage(u32),
+
+    /// Too few participants behaved honestly for the protocol to complete.
+    NotEnoughParticipants,
+
+    /// Too few valid fragments were given to assemble a valid `Signature`, or
+    /// to reshare a key.
+    NotEnoughFragments,
+
+    /// A dealing of a refresh round isn't a valid sharing of zero for the
+    /// fragment being refreshed. Holds the position of the dealing.
+    InvalidRefreshShare(usize),
+
+    /// A dealing of a resharing doesn't reshare its dealer's fragment to the
+    /// fragment being combined. Holds the position of the dealing.
+    InvalidReshare(usize),
+
+    /// A signature didn't verify under its public key.
+    InvalidSignature,
+
+    /// A serialized key is of another type of key. Holds its tag byte.
+    InvalidKeyType(u8),
+
+    /// A serialized protocol message has an unknown type. Holds its tag byte.
+    InvalidMessageType(u8),
+
+    /// A key scoped to an epoch was asked of `DesignatedKeyKdf::Legacy`, which
+    /// can't bind an epoch.
+    UnsupportedEpoch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidLength(length) => write!(f, "invalid length: {} bytes", length),
+            Error::NonCanonicalScalar => write!(f, "scalar is not canonical"),
+            Error::PointNotOnCurve => write!(f, "point is not on the curve"),
+            Error::PointNotInSubgroup => write!(f, "point is not in the prime order subgroup"),
+            Error::IdentityPoint => write!(f, "point is the identity"),
+            Error::InvalidFragmentIndex => write!(f, "fragment index is not valid"),
+            Error::DuplicateFragmentIndex => write!(f, "fragment index is duplicated"),
+            Error::InvalidParticipant(id) => write!(f, "unknown participant: {}", id),
+            Error::WrongRound => write!(f, "message or step is for the wrong round"),
+            Error::DuplicateMessage(id) => write!(f, "duplicate message from participant {}", id),
+            Error::NotEnoughParticipants => write!(f, "not enough honest participants"),
+            Error::NotEnoughFragments => write!(f, "not enough valid fragments"),
+            Error::InvalidRefreshShare(position) => {
+                write!(f, "refresh dealing {} is not valid", position)
+            }
+            Error::InvalidReshare(position) => {
+                write!(f, "reshare dealing {} is not valid", position)
+            }
+            Error::InvalidSignature => write!(f, "signature is not valid"),
+            Error::InvalidKeyType(tag) => write!(f, "key has the wrong type tag {}", tag),
+            Error::InvalidMessageType(tag) => write!(f, "unknown message type tag {}", tag),
+            Error::UnsupportedEpoch => {
+                write!(f, "the legacy key derivation can't be scoped to an epoch")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
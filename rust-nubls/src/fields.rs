@@ -0,0 +1,516 @@
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// The field modulus `p` of BLS12-381, as little-endian 64-bit limbs.
+const MODULUS: [u64; 6] = [
+    0xb9fe_ffff_ffff_aaab,
+    0x1eab_fffe_b153_ffff,
+    0x6730_d2a0_f6b0_f624,
+    0x6477_4b84_f385_12bf,
+    0x4b1b_a7b6_434b_acd7,
+    0x1a01_11ea_397f_e69a,
+];
+
+/// INV = -(p^{-1} mod 2^64) mod 2^64
+const INV: u64 = 0x89f3_fffc_fffc_fffd;
+
+/// R = 2^384 mod p
+const R: Fp = Fp([
+    0x7609_0000_0002_fffd,
+    0xebf4_000b_c40c_0002,
+    0x5f48_9857_53c7_58ba,
+    0x77ce_5853_7052_5745,
+    0x5c07_1a97_a256_ec6d,
+    0x15f6_5ec3_fa80_e493,
+]);
+
+/// R2 = 2^(384*2) mod p
+const R2: Fp = Fp([
+    0xf4df_1f34_1c34_1746,
+    0x0a76_e6a6_09d1_04f1,
+    0x8de5_476c_4c95_b6d5,
+    0x67eb_88a9_939d_83c0,
+    0x9a79_3e85_b519_952d,
+    0x1198_8fe5_92ca_e3aa,
+]);
+
+/// R3 = 2^(384*3) mod p
+const R3: Fp = Fp([
+    0xed48_ac6b_d94c_a1e0,
+    0x315f_831e_03a7_adf8,
+    0x9a53_352a_615e_29dd,
+    0x34c0_4e5e_921e_1761,
+    0x2512_d435_6572_4728,
+    0x0aa6_3460_9175_5d4d,
+]);
+
+/// p - 2, used to compute inverses with Fermat's little theorem.
+const P_MINUS_2: [u64; 6] = [
+    0xb9fe_ffff_ffff_aaa9,
+    0x1eab_fffe_b153_ffff,
+    0x6730_d2a0_f6b0_f624,
+    0x6477_4b84_f385_12bf,
+    0x4b1b_a7b6_434b_acd7,
+    0x1a01_11ea_397f_e69a,
+];
+
+/// (p - 3) / 4, used by the `Fp2` square root.
+const P_MINUS_3_DIV_4: [u64; 6] = [
+    0xee7f_bfff_ffff_eaaa,
+    0x07aa_ffff_ac54_ffff,
+    0xd9cc_34a8_3dac_3d89,
+    0xd91d_d2e1_3ce1_44af,
+    0x92c6_e9ed_90d2_eb35,
+    0x0680_447a_8e5f_f9a6,
+];
+
+/// (p - 1) / 2, used by the `Fp2` square root.
+const P_MINUS_1_DIV_2: [u64; 6] = [
+    0xdcff_7fff_ffff_d555,
+    0x0f55_ffff_58a9_ffff,
+    0xb398_6950_7b58_7b12,
+    0xb23b_a5c2_79c2_895f,
+    0x258d_d3db_21a5_d66b,
+    0x0d00_88f5_1cbf_f34d,
+];
+
+/// Computes `a + b + carry`, returning the result and the new carry.
+#[inline(always)]
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let ret = (a as u128) + (b as u128) + (carry as u128);
+    (ret as u64, (ret >> 64) as u64)
+}
+
+/// Computes `a - (b + borrow)`, returning the result and the new borrow.
+#[inline(always)]
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let ret = (a as u128).wrapping_sub((b as u128) + ((borrow >> 63) as u128));
+    (ret as u64, (ret >> 64) as u64)
+}
+
+/// Computes `a + (b * c) + carry`, returning the result and the new carry.
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
+    (ret as u64, (ret >> 64) as u64)
+}
+
+/// An element of the base field `F_p` of BLS12-381, kept in Montgomery form.
+///
+/// The `bls12_381` crate keeps its field types private, so this is a minimal
+/// re-implementation of the arithmetic needed to map messages to G_2.
+/// It is _not_ constant-time, and must only be used on public data such as
+/// the messages being signed.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub(crate) struct Fp([u64; 6]);
+
+impl Fp {
+    /// Returns the additive identity.
+    pub(crate) const fn zero() -> Fp {
+        Fp([0, 0, 0, 0, 0, 0])
+    }
+
+    /// Returns the multiplicative identity.
+    pub(crate) const fn one() -> Fp {
+        R
+    }
+
+    /// Creates an `Fp` from limbs that are already in Montgomery form.
+    pub(crate) const fn from_raw_unchecked(limbs: [u64; 6]) -> Fp {
+        Fp(limbs)
+    }
+
+    /// Reduces a 64-byte big-endian integer modulo `p`.
+    ///
+    /// This is the `OS2IP(tv) mod p` step of `hash_to_field`.
+    pub(crate) fn from_bytes_wide(bytes: &[u8; 64]) -> Fp {
+        // Split the integer into `hi * 2^384 + lo`, where `hi` is 128 bits.
+        let mut hi = [0u64; 6];
+        let mut lo = [0u64; 6];
+        for i in 0..2 {
+            let mut limb = [0u8; 8];
+            limb.copy_from_slice(&bytes[8 * i..8 * (i + 1)]);
+            hi[1 - i] = u64::from_be_bytes(limb);
+        }
+        for i in 0..6 {
+            let mut limb = [0u8; 8];
+            limb.copy_from_slice(&bytes[16 + 8 * i..16 + 8 * (i + 1)]);
+            lo[5 - i] = u64::from_be_bytes(limb);
+        }
+
+        // Multiplying by R2 (resp. R3) moves `lo` (resp. `hi * 2^384`) into
+        // Montgomery form. The Montgomery reduction handles `lo >= p`.
+        Fp(lo) * R2 + Fp(hi) * R3
+    }
+
+    /// Serializes the element to 48 big-endian bytes in canonical form.
+    pub(crate) fn to_bytes(self) -> [u8; 48] {
+        let canonical = self.to_canonical();
+        let mut res = [0u8; 48];
+        for (i, limb) in canonical.iter().rev().enumerate() {
+            res[8 * i..8 * (i + 1)].copy_from_slice(&limb.to_be_bytes());
+        }
+        res
+    }
+
+    /// Returns whether or not this element is zero.
+    pub(crate) fn is_zero(&self) -> bool {
+        self.0 == [0, 0, 0, 0, 0, 0]
+    }
+
+    /// Returns the `sgn0` of the element, i.e. the parity of its canonical form.
+    pub(crate) fn sgn0(&self) -> bool {
+        self.to_canonical()[0] & 1 == 1
+    }
+
+    pub(crate) fn square(&self) -> Fp {
+        *self * *self
+    }
+
+    /// Exponentiates `self` by `by`, a little-endian sequence of limbs.
+    /// This is variable-time with respect to the exponent.
+    pub(crate) fn pow_vartime(&self, by: &[u64; 6]) -> Fp {
+        let mut res = Fp::one();
+        for limb in by.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.square();
+                if ((limb >> i) & 1) == 1 {
+                    res = res * *self;
+                }
+            }
+        }
+        res
+    }
+
+    /// Computes the multiplicative inverse, returning `None` for zero.
+    pub(crate) fn invert(&self) -> Option<Fp> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.pow_vartime(&P_MINUS_2))
+        }
+    }
+
+    /// Computes a square root, returning `None` if `self` is not a square.
+    ///
+    /// Since p = 3 mod 4, the candidate root is `self^((p+1)/4)`.
+    pub(crate) fn sqrt(&self) -> Option<Fp> {
+        let candidate = self.pow_vartime(&P_MINUS_3_DIV_4) * *self;
+        if candidate.square() == *self {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// Converts out of Montgomery form, returning the canonical limbs.
+    fn to_canonical(self) -> [u64; 6] {
+        (self * Fp([1, 0, 0, 0, 0, 0])).0
+    }
+
+    /// Subtracts `p` from `limbs` if `limbs >= p`.
+    fn subtract_p(limbs: [u64; 6]) -> Fp {
+        let mut reduced = [0u64; 6];
+        let mut borrow = 0;
+        for i in 0..6 {
+            let (limb, b) = sbb(limbs[i], MODULUS[i], borrow);
+            reduced[i] = limb;
+            borrow = b;
+        }
+        // A final borrow means `limbs < p`, so we keep the original.
+        if borrow != 0 {
+            Fp(limbs)
+        } else {
+            Fp(reduced)
+        }
+    }
+}
+
+impl Add for Fp {
+    type Output = Fp;
+
+    fn add(self, rhs: Fp) -> Fp {
+        // Both inputs are below p < 2^381, so the sum can't overflow 384 bits.
+        let mut sum = [0u64; 6];
+        let mut carry = 0;
+        for (limb, (a, b)) in sum.iter_mut().zip(self.0.iter().zip(rhs.0.iter())) {
+            let (res, c) = adc(*a, *b, carry);
+            *limb = res;
+            carry = c;
+        }
+        Fp::subtract_p(sum)
+    }
+}
+
+impl Neg for Fp {
+    type Output = Fp;
+
+    fn neg(self) -> Fp {
+        if self.is_zero() {
+            return self;
+        }
+        let mut res = [0u64; 6];
+        let mut borrow = 0;
+        for i in 0..6 {
+            let (limb, b) = sbb(MODULUS[i], self.0[i], borrow);
+            res[i] = limb;
+            borrow = b;
+        }
+        Fp(res)
+    }
+}
+
+impl Sub for Fp {
+    type Output = Fp;
+
+    fn sub(self, rhs: Fp) -> Fp {
+        self + (-rhs)
+    }
+}
+
+impl Mul for Fp {
+    type Output = Fp;
+
+    /// Montgomery multiplication using the CIOS method.
+    fn mul(self, rhs: Fp) -> Fp {
+        let mut t = [0u64; 8];
+        for i in 0..6 {
+            // t += self * rhs[i]
+            let mut carry = 0;
+            for (t_j, a_j) in t.iter_mut().zip(self.0.iter()) {
+                let (limb, c) = mac(*t_j, *a_j, rhs.0[i], carry);
+                *t_j = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[6], carry, 0);
+            t[6] = limb;
+            t[7] = c;
+
+            // t = (t + m * p) / 2^64
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+            for j in 1..6 {
+                let (limb, c) = mac(t[j], m, MODULUS[j], carry);
+                t[j - 1] = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[6], carry, 0);
+            t[5] = limb;
+            t[6] = t[7] + c;
+        }
+
+        let mut res = [0u64; 6];
+        res.copy_from_slice(&t[0..6]);
+        Fp::subtract_p(res)
+    }
+}
+
+/// An element of the quadratic extension `F_{p^2} = F_p[I] / (I^2 + 1)`,
+/// represented as `c0 + c1 * I`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub(crate) struct Fp2 {
+    pub(crate) c0: Fp,
+    pub(crate) c1: Fp,
+}
+
+impl Fp2 {
+    pub(crate) const fn zero() -> Fp2 {
+        Fp2 {
+            c0: Fp::zero(),
+            c1: Fp::zero(),
+        }
+    }
+
+    pub(crate) const fn one() -> Fp2 {
+        Fp2 {
+            c0: Fp::one(),
+            c1: Fp::zero(),
+        }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    /// Returns the `sgn0` of the element as defined for `m = 2` extensions
+    /// in the IETF hash_to_curve specification.
+    pub(crate) fn sgn0(&self) -> bool {
+        let sign_0 = self.c0.sgn0();
+        let zero_0 = self.c0.is_zero();
+        let sign_1 = self.c1.sgn0();
+        sign_0 || (zero_0 && sign_1)
+    }
+
+    pub(crate) fn square(&self) -> Fp2 {
+        *self * *self
+    }
+
+    /// Exponentiates `self` by `by`, a little-endian sequence of limbs.
+    /// This is variable-time with respect to the exponent.
+    pub(crate) fn pow_vartime(&self, by: &[u64; 6]) -> Fp2 {
+        let mut res = Fp2::one();
+        for limb in by.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.square();
+                if ((limb >> i) & 1) == 1 {
+                    res = res * *self;
+                }
+            }
+        }
+        res
+    }
+
+    /// Computes the multiplicative inverse, returning `None` for zero.
+    pub(crate) fn invert(&self) -> Option<Fp2> {
+        // 1 / (c0 + c1 * I) = (c0 - c1 * I) / (c0^2 + c1^2)
+        (self.c0.square() + self.c1.square()).invert().map(|t| Fp2 {
+            c0: self.c0 * t,
+            c1: -(self.c1 * t),
+        })
+    }
+
+    /// Computes a square root, returning `None` if `self` is not a square.
+    ///
+    /// This is Algorithm 9 of https://eprint.iacr.org/2012/685.pdf, which
+    /// applies since p = 3 mod 4.
+    pub(crate) fn sqrt(&self) -> Option<Fp2> {
+        if self.is_zero() {
+            return Some(Fp2::zero());
+        }
+
+        let a1 = self.pow_vartime(&P_MINUS_3_DIV_4);
+        let alpha = a1.square() * *self;
+        let x0 = a1 * *self;
+
+        let candidate = if alpha == -Fp2::one() {
+            // Multiply by I.
+            Fp2 {
+                c0: -x0.c1,
+                c1: x0.c0,
+            }
+        } else {
+            (alpha + Fp2::one()).pow_vartime(&P_MINUS_1_DIV_2) * x0
+        };
+
+        if candidate.square() == *self {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Fp2;
+
+    fn add(self, rhs: Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Fp2;
+
+    fn neg(self) -> Fp2 {
+        Fp2 {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Fp2;
+
+    fn sub(self, rhs: Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Fp2;
+
+    fn mul(self, rhs: Fp2) -> Fp2 {
+        // (a + bI)(c + dI) = (ac - bd) + (ad + bc)I
+        Fp2 {
+            c0: self.c0 * rhs.c0 - self.c1 * rhs.c1,
+            c1: self.c0 * rhs.c1 + self.c1 * rhs.c0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fp_arithmetic() {
+        let two = Fp::one() + Fp::one();
+        let three = two + Fp::one();
+        assert_eq!(two * three, three + three);
+        assert_eq!(three - two, Fp::one());
+        assert_eq!(-Fp::one() + Fp::one(), Fp::zero());
+        assert_eq!(three * three.invert().unwrap(), Fp::one());
+        assert_eq!(Fp::zero().invert(), None);
+
+        // `sgn0` is taken on the canonical representation.
+        assert!(Fp::one().sgn0());
+        assert!(!two.sgn0());
+        assert!((-Fp::one()).to_bytes()[47] & 1 == 0);
+    }
+
+    #[test]
+    fn test_fp_sqrt() {
+        let two = Fp::one() + Fp::one();
+        let four = two.square();
+        let root = four.sqrt().unwrap();
+        assert!(root == two || root == -two);
+
+        // 5 is not a square modulo p.
+        assert_eq!((four + Fp::one()).sqrt(), None);
+        assert_eq!(Fp::zero().sqrt(), Some(Fp::zero()));
+    }
+
+    #[test]
+    fn test_fp_from_bytes_wide() {
+        let mut one = [0u8; 64];
+        one[63] = 1;
+        assert_eq!(Fp::from_bytes_wide(&one), Fp::one());
+
+        // p itself reduces to zero, and p + 1 to one.
+        let mut p = [0u8; 64];
+        for (i, limb) in MODULUS.iter().rev().enumerate() {
+            p[16 + 8 * i..16 + 8 * (i + 1)].copy_from_slice(&limb.to_be_bytes());
+        }
+        assert_eq!(Fp::from_bytes_wide(&p), Fp::zero());
+        p[63] += 1;
+        assert_eq!(Fp::from_bytes_wide(&p), Fp::one());
+
+        // 2^384 mod p is R, which is R2 in Montgomery form.
+        let mut r = [0u8; 64];
+        r[15] = 1;
+        assert_eq!(Fp::from_bytes_wide(&r), R2);
+    }
+
+    #[test]
+    fn test_fp2_sqrt() {
+        let a = Fp2 {
+            c0: Fp::one() + Fp::one(),
+            c1: Fp::one(),
+        };
+        let a_sq = a.square();
+        let root = a_sq.sqrt().unwrap();
+        assert!(root == a || root == -a);
+
+        // -(2 + I) is the non-square `Z` used by SSWU.
+        let z = -a;
+        assert_eq!(z.sqrt(), None);
+
+        assert_eq!(a * a.invert().unwrap(), Fp2::one());
+    }
+}
@@ -0,0 +1,193 @@
+use bls12_381::{G1Affine, G2Affine};
+use sha2::{Digest, Sha256};
+
+mod g1;
+mod g2;
+
+/// The number of bytes hashed into each `Fp` element, `L = ceil((ceil(log2(p)) + k) / 8)`
+/// with a security parameter of `k = 128`.
+const HASH_TO_FIELD_L: usize = 64;
+
+/// The byte-length of a SHA-256 digest, `b_in_bytes`.
+const SHA256_OUTPUT_BYTES: usize = 32;
+
+/// The input block size of SHA-256, `r_in_bytes`.
+const SHA256_BLOCK_BYTES: usize = 64;
+
+/// The prefix used to hash domain separation tags longer than 255 bytes.
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// Hashes an arbitrary `msg` to a point in the G_1 group of BLS12-381 under
+/// the domain separation tag `dst`.
+///
+/// This implements the `BLS12381G1_XMD:SHA-256_SSWU_RO_` suite of the IETF
+/// hash_to_curve specification, which mirrors `hash_to_g2` with `Fp` elements
+/// and the 11-isogeny to E1.
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Affine {
+    let [u_0, u_1] = g1::hash_to_field(msg, dst);
+    let q_0 = g1::map_to_curve(&u_0);
+    let q_1 = g1::map_to_curve(&u_1);
+    (q_0 + q_1).clear_cofactor().into()
+}
+
+/// Hashes an arbitrary `msg` to a point in the G_2 group of BLS12-381 under
+/// the domain separation tag `dst`.
+///
+/// This implements the `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite of the IETF
+/// hash_to_curve specification: the message is expanded with `expand_message_xmd`,
+/// hashed to two `Fp2` elements, each of which is mapped to the curve with
+/// the simplified SWU map and the 3-isogeny, and finally the sum of the two
+/// points has its cofactor cleared.
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    let [u_0, u_1] = g2::hash_to_field(msg, dst);
+    let q_0 = g2::map_to_curve(&u_0);
+    let q_1 = g2::map_to_curve(&u_1);
+    (q_0 + q_1).clear_cofactor().into()
+}
+
+/// Expands `msg` into `len_in_bytes` uniformly random bytes with SHA-256 as
+/// specified by `expand_message_xmd`.
+///
+/// This will panic if `len_in_bytes` is larger than `255 * 32` bytes.
+pub(crate) fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = (len_in_bytes + SHA256_OUTPUT_BYTES - 1) / SHA256_OUTPUT_BYTES;
+    assert!(
+        ell <= 255,
+        "Requested too many bytes from expand_message_xmd."
+    );
+
+    // Tags longer than 255 bytes are hashed down first.
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = Sha256::new()
+            .chain(OVERSIZE_DST_PREFIX)
+            .chain(dst)
+            .finalize();
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let b_0 = Sha256::new()
+        .chain([0u8; SHA256_BLOCK_BYTES])
+        .chain(msg)
+        .chain((len_in_bytes as u16).to_be_bytes())
+        .chain([0u8])
+        .chain(dst)
+        .chain(dst_len)
+        .finalize();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    let mut b_i = Sha256::new()
+        .chain(b_0)
+        .chain([1u8])
+        .chain(dst)
+        .chain(dst_len)
+        .finalize();
+
+    let mut uniform_bytes = Vec::<u8>::with_capacity(ell * SHA256_OUTPUT_BYTES);
+    uniform_bytes.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        // b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)
+        let mut xored = [0u8; SHA256_OUTPUT_BYTES];
+        for (x, (b_0, b_prev)) in xored.iter_mut().zip(b_0.iter().zip(b_i.iter())) {
+            *x = b_0 ^ b_prev;
+        }
+        b_i = Sha256::new()
+            .chain(xored)
+            .chain([i as u8])
+            .chain(dst)
+            .chain(dst_len)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::from_hex;
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // Test vectors from the IETF hash_to_curve specification, Appendix K.1.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let q128 = [&b"q128_"[..], &[b'q'; 128][..]].concat();
+        let a512 = [&b"a512_"[..], &[b'a'; 512][..]].concat();
+
+        let vectors: [(&[u8], usize, &str); 7] = [
+            (b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (b"abcdef0123456789", 0x20, "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+            (&q128, 0x20, "b23a1d2b4d97b2ef7785562a7e8bac7eed54ed6e97e29aa51bfe3f12ddad1ff9"),
+            (&a512, 0x20, "4623227bcc01293b8c130bf771da8c298dede7383243dc0993d2d94823958c4c"),
+            (b"", 0x80, "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"),
+            (b"abc", 0x80, "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40"),
+        ];
+        for (msg, len_in_bytes, expected) in vectors.iter() {
+            assert_eq!(
+                expand_message_xmd(msg, dst, *len_in_bytes),
+                from_hex(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_to_g1() {
+        // Test vectors from the IETF hash_to_curve specification, Appendix J.9.1.
+        // The expected points are given in their uncompressed serialization.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let q128 = [&b"q128_"[..], &[b'q'; 128][..]].concat();
+        let a512 = [&b"a512_"[..], &[b'a'; 512][..]].concat();
+
+        let vectors: [(&[u8], &str); 5] = [
+            (b"", "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a108ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265"),
+            (b"abc", "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f69030b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d"),
+            (b"abcdef0123456789", "11e0b079dea29a68f0383ee94fed1b940995272407e3bb916bbf268c263ddd57a6a27200a784cbc248e84f357ce82d9803a87ae2caf14e8ee52e51fa2ed8eefe80f02457004ba4d486d6aa1f517c0889501dc7413753f9599b099ebcbbd2d709"),
+            (&q128, "15f68eaa693b95ccb85215dc65fa81038d69629f70aeee0d0f677cf22285e7bf58d7cb86eefe8f2e9bc3f8cb84fac4881807a1d50c29f430b8cafc4f8638dfeeadf51211e1602a5f184443076715f91bb90a48ba1e370edce6ae1062f5e6dd38"),
+            (&a512, "082aabae8b7dedb0e78aeb619ad3bfd9277a2f77ba7fad20ef6aabdc6c31d19ba5a6d12283553294c1825c4b3ca2dcfe05b84ae5a942248eea39e1d91030458c40153f3b654ab7872d779ad1e942856a20c438e8d99bc8abfbf74729ce1f7ac8"),
+        ];
+        for (msg, expected) in vectors.iter() {
+            assert_eq!(
+                hash_to_g1(msg, dst).to_uncompressed()[..],
+                from_hex(expected)[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_to_g2() {
+        // Test vectors from the IETF hash_to_curve specification, Appendix J.10.1.
+        // The expected points are given in their compressed serialization.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let q128 = [&b"q128_"[..], &[b'q'; 128][..]].concat();
+        let a512 = [&b"a512_"[..], &[b'a'; 512][..]].concat();
+
+        let vectors: [(&[u8], &str); 5] = [
+            (b"", "a5cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a"),
+            (b"abc", "939cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd802c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6"),
+            (b"abcdef0123456789", "990d119345b94fbd15497bcba94ecf7db2cbfd1e1fe7da034d26cbba169fb3968288b3fafb265f9ebd380512a71c3f2c121982811d2491fde9ba7ed31ef9ca474f0e1501297f68c298e9f4c0028add35aea8bb83d53c08cfc007c1e005723cd0"),
+            (&q128, "8934aba516a52d8ae479939a91998299c76d39cc0c035cd18813bec433f587e2d7a4fef038260eef0cef4d02aae3eb9119a84dd7248a1066f737cc34502ee5555bd3c19f2ecdb3c7d9e24dc65d4e25e50d83f0f77105e955d78f4762d33c17da"),
+            (&a512, "91fca2ff525572795a801eed17eb12785887c7b63fb77a42be46ce4a34131d71f7a73e95fee3f812aea3de78b4d0156901a6ba2f9a11fa5598b2d8ace0fbe0a0eacb65deceb476fbbcb64fd24557c2f4b18ecfc5663e54ae16a84f5ab7f62534"),
+        ];
+        for (msg, expected) in vectors.iter() {
+            assert_eq!(
+                hash_to_g2(msg, dst).to_compressed()[..],
+                from_hex(expected)[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_to_g2_is_in_subgroup() {
+        let point = hash_to_g2(b"NuBLS!", b"NUBLS-TEST-DST");
+        assert!(bool::from(point.is_torsion_free()));
+        assert!(bool::from(point.is_on_curve()));
+        assert!(!bool::from(point.is_identity()));
+        assert_ne!(point, hash_to_g2(b"NuBLS!", b"NUBLS-OTHER-TEST-DST"));
+    }
+}
@@ -0,0 +1,176 @@
+use bls12_381::{G1Affine, Scalar};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::error::Error;
+use crate::keys::PublicKey;
+
+/// The domain separation tag that NuBLS derives designated keys under with
+/// `DesignatedKeyKdf::HkdfSha256`, for applications without their own.
+pub const DESIGNATED_KEY_DST: &[u8] = b"NUBLS_PRS_DESIGNATED_KEY_";
+
+/// The domain separation tag that the designated keys behind the shares of
+/// bidirectional re-signing keys are derived under, so that a share never
+/// re-signs signatures under a Penumbral designated key.
+pub(crate) const BIDIRECTIONAL_KEY_DST: &[u8] = b"NUBLS_PRS_BIDIRECTIONAL_KEY_";
+
+/// The version tag of the HKDF designated key derivation, bound into its info
+/// field so that keys derived by later versions never collide with these.
+const DESIGNATED_KEY_VERSION: &[u8] = b"NUBLS-PRS-KDF-V1";
+
+/// A `DesignatedKeyKdf` selects how Penumbral derives Bob's designated key for
+/// Alice from their Diffie-Hellman shared point:
+///
+///  1. `HkdfSha256` -- HKDF-SHA256 with the given domain separation tag as the
+///     salt, and a version tag and both parties' compressed public keys in the
+///     info field. Keys for different applications, protocol versions, or
+///     pairs of parties never collide.
+///
+///  2. `Legacy` -- SHA-512 of the uncompressed shared point, as derived by
+///     older versions of NuBLS. Only use this to interoperate with them.
+///
+/// Both parties must use the same `DesignatedKeyKdf` to agree on the key.
+/// The default is `Legacy`, so that the keys derived by `PRSKey::designated_key`
+/// and `PRSKey::resigning_key` keep matching those of earlier versions. New
+/// deployments should pass `HkdfSha256` to the `PRSKey::*_with_kdf` methods.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum DesignatedKeyKdf<'a> {
+    HkdfSha256(&'a [u8]),
+    Legacy,
+}
+
+impl Default for DesignatedKeyKdf<'_> {
+    fn default() -> Self {
+        DesignatedKeyKdf::Legacy
+    }
+}
+
+impl DesignatedKeyKdf<'_> {
+    /// Derives the designated key from the Diffie-Hellman `shared_point`
+    /// between the two parties with public keys `key_a` and `key_b`.
+    ///
+    /// The public keys are bound in a canonical order, so both parties derive
+    /// the same key regardless of which of them is `key_a`.
+    pub(crate) fn derive(
+        &self,
+        shared_point: &G1Affine,
+        key_a: &PublicKey,
+        key_b: &PublicKey,
+    ) -> Scalar {
+        match self {
+            DesignatedKeyKdf::Legacy => {
+                let mut okm = [0u8; 64];
+                okm.copy_from_slice(&Sha512::digest(&shared_point.to_uncompressed()));
+                Scalar::from_bytes_wide(&okm)
+            }
+            DesignatedKeyKdf::HkdfSha256(dst) => hkdf_derive(dst, shared_point, key_a, key_b, b""),
+        }
+    }
+
+    /// Derives the designated key scoped to the `epoch` label, as in `derive`,
+    /// but with the `epoch` bound after the public keys. The empty label
+    /// derives the unscoped key.
+    ///
+    /// Returns `Error::UnsupportedEpoch` for `Legacy`, which can't be scoped.
+    pub(crate) fn derive_for_epoch(
+        &self,
+        shared_point: &G1Affine,
+        key_a: &PublicKey,
+        key_b: &PublicKey,
+        epoch: &[u8],
+    ) -> Result<Scalar, Error> {
+        match self {
+            DesignatedKeyKdf::Legacy => Err(Error::UnsupportedEpoch),
+            DesignatedKeyKdf::HkdfSha256(dst) => {
+                Ok(hkdf_derive(dst, shared_point, key_a, key_b, epoch))
+            }
+        }
+    }
+}
+
+/// Derives a designated key with HKDF-SHA256, with the `dst` as the salt, the
+/// compressed `shared_point` as the input keying material, and the version
+/// tag, both public keys, and the `epoch` in the info field.
+fn hkdf_derive(
+    dst: &[u8],
+    shared_point: &G1Affine,
+    key_a: &PublicKey,
+    key_b: &PublicKey,
+    epoch: &[u8],
+) -> Scalar {
+    let (key_a, key_b) = (key_a.0.to_compressed(), key_b.0.to_compressed());
+    let (low, high) = if key_a <= key_b {
+        (key_a, key_b)
+    } else {
+        (key_b, key_a)
+    };
+    let mut info = Vec::with_capacity(DESIGNATED_KEY_VERSION.len() + 2 * low.len() + epoch.len());
+    info.extend_from_slice(DESIGNATED_KEY_VERSION);
+    info.extend_from_slice(&low);
+    info.extend_from_slice(&high);
+    info.extend_from_slice(epoch);
+
+    let mut okm = [0u8; 64];
+    Hkdf::<Sha256>::new(Some(dst), &shared_point.to_compressed())
+        .expand(&info, &mut okm)
+        .expect("64 bytes is a valid HKDF-SHA256 output length");
+    Scalar::from_bytes_wide(&okm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::PrivateKey;
+
+    #[test]
+    fn test_designated_key_kdf() {
+        let priv_a = PrivateKey::random();
+        let priv_b = PrivateKey::random();
+        let (pub_a, pub_b) = (priv_a.public_key(), priv_b.public_key());
+        let shared_point = G1Affine::from(pub_a.0 * priv_b.0);
+
+        // Both parties derive the same key, in either order.
+        let kdf = DesignatedKeyKdf::HkdfSha256(DESIGNATED_KEY_DST);
+        let key = kdf.derive(&shared_point, &pub_a, &pub_b);
+        assert_eq!(kdf.derive(&shared_point, &pub_b, &pub_a), key);
+
+        // Keys are separated by DST, by derivation, and by the parties.
+        let app_key =
+            DesignatedKeyKdf::HkdfSha256(b"NUBLS_TEST_APP_").derive(&shared_point, &pub_a, &pub_b);
+        assert_ne!(app_key, key);
+        let legacy_key = DesignatedKeyKdf::Legacy.derive(&shared_point, &pub_a, &pub_b);
+        assert_ne!(legacy_key, key);
+        let pub_c = PrivateKey::random().public_key();
+        assert_ne!(kdf.derive(&shared_point, &pub_a, &pub_c), key);
+
+        // And by epoch, where the empty epoch is the unscoped key.
+        let derive_for_epoch = |kdf: DesignatedKeyKdf, epoch: &[u8]| {
+            kdf.derive_for_epoch(&shared_point, &pub_a, &pub_b, epoch)
+        };
+        assert_eq!(derive_for_epoch(kdf, b""), Ok(key));
+        let epoch_key = derive_for_epoch(kdf, b"epoch-1").unwrap();
+        assert_ne!(epoch_key, key);
+        assert_eq!(
+            kdf.derive_for_epoch(&shared_point, &pub_b, &pub_a, b"epoch-1"),
+            Ok(epoch_key)
+        );
+        assert_ne!(derive_for_epoch(kdf, b"epoch-2"), Ok(epoch_key));
+
+        // Epoch keys are separated by DST too, and the legacy derivation
+        // can't be scoped at all.
+        let app_kdf = DesignatedKeyKdf::HkdfSha256(b"NUBLS_TEST_APP_");
+        assert_eq!(derive_for_epoch(app_kdf, b""), Ok(app_key));
+        assert_ne!(derive_for_epoch(app_kdf, b"epoch-1"), Ok(epoch_key));
+        assert_eq!(
+            derive_for_epoch(DesignatedKeyKdf::Legacy, b"epoch-1"),
+            Err(Error::UnsupportedEpoch)
+        );
+
+        // The legacy derivation is SHA-512 of the uncompressed shared point,
+        // and stays the default.
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(&Sha512::digest(&shared_point.to_uncompressed()));
+        assert_eq!(legacy_key, Scalar::from_bytes_wide(&digest));
+        assert_eq!(DesignatedKeyKdf::default(), DesignatedKeyKdf::Legacy);
+    }
+}
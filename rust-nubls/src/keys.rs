@@ -1,17 +1,28 @@
 use crate::bls::{Signature, VerificationResult};
+use crate::ciphersuite::{key_validate, Ciphersuite, POP_TAG};
+use crate::error::Error;
+use crate::hash_to_curve::hash_to_g2;
+use crate::kdf::DesignatedKeyKdf;
+use crate::prs::{DesignatedKey, ResigningKey};
 use crate::traits::{PRSKey, ThresholdKey};
-use crate::utils::{lambda_coeff, poly_eval};
+use crate::utils::{
+    fragment_id, fragment_index, fragment_index_from_bytes, fragment_index_length,
+    fragment_index_to_bytes, fragment_indices, g1_from_bytes, lambda_coeff, poly_eval, random_poly,
+    scalar_from_bytes,
+};
+use crate::vss::{BlindingShare, FeldmanCommitments, PedersenCommitments};
 
-use bls12_381::{G1Affine, G2Affine, Scalar};
-use getrandom;
-
-use sha2::{Digest, Sha512};
+use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
 
 pub(crate) const SCALAR_BYTES_LENGTH: usize = 32;
 
+pub(crate) const G1_POINT_BYTES_LENGTH: usize = 48;
+
 /// A `PublicKey` represents an Affine element of the G_1 group on the BLS12-381 curve.
+/// We have an `Option<Scalar>` field for a Fragment ID in the case of Threshold signatures,
+/// where the `PublicKey` of a fragment verifies its signature fragments.
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
-pub struct PublicKey(pub(crate) G1Affine);
+pub struct PublicKey(pub(crate) G1Affine, pub(crate) Option<Scalar>);
 
 /// A `PrivateKey` represents a Scalar element within the order of the BLS12-381 curve.
 /// We have an `Option<Scalar>` field for a Fragment ID in the case of Threshold signatures.
@@ -23,96 +34,364 @@ impl PrivateKey {
     pub fn random() -> PrivateKey {
         let mut key_bytes = [0u8; 64];
         match getrandom::getrandom(&mut key_bytes) {
-            Ok(_) => return PrivateKey(Scalar::from_bytes_wide(&key_bytes), None),
+            Ok(_) => PrivateKey(Scalar::from_bytes_wide(&key_bytes), None),
             Err(err) => panic!("Error while generating a random key: {:?}", err),
-        };
+        }
     }
 
     /// Returns the corresponding `PublicKey` of the `PrivateKey`.
+    ///
+    /// The `PublicKey` of a fragment carries its fragment ID, and verifies
+    /// the fragment's signatures with `PublicKey::verify_fragment`.
     pub fn public_key(&self) -> PublicKey {
         // The BLS12_381 API doesn't work with additive notation, apparently.
-        PublicKey((&G1Affine::generator() * &self.0).into())
+        PublicKey((G1Affine::generator() * self.0).into(), self.1)
     }
 
     /// Signs a `message_element` and returns a `Signature`.
     ///
-    /// The `sign` API presently only works with messages already mapped to the
-    /// G_2 group on BLS12-381 (see https://github.com/nucypher/NuBLS/issues/1).
-    ///
-    /// TODO: Implement `hash_to_curve` per the IETF hash_to_curve specification.
+    /// The `sign` API works with messages already mapped to the G_2 group on
+    /// BLS12-381. To sign raw message bytes, use `sign_message`.
     pub fn sign(&self, message_element: &G2Affine) -> Signature {
         Signature::new(self, message_element)
     }
 
+    /// Signs the raw `message` bytes and returns a `Signature`.
+    ///
+    /// This signs under the `Ciphersuite::Basic` scheme; use `Ciphersuite`
+    /// directly to sign under one of the other schemes.
+    pub fn sign_message(&self, message: &[u8]) -> Signature {
+        Ciphersuite::Basic.sign(self, message)
+    }
+
+    /// Returns a proof of possession of the `PrivateKey`.
+    ///
+    /// This is a signature over the serialized `PublicKey` under the proof of
+    /// possession DST, `BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`. Checking
+    /// it with `PublicKey::verify_possession` before admitting a key into an
+    /// aggregate set protects against rogue key attacks.
+    pub fn prove_possession(&self) -> Signature {
+        let public_key = self.public_key();
+        PrivateKey(self.0, None).sign(&hash_to_g2(&public_key.0.to_compressed(), POP_TAG))
+    }
+
+    /// Splits the private key into `n` fragments with a threshold of `m`, like
+    /// `ThresholdKey::split`, using Feldman's Verifiable Secret Sharing.
+    ///
+    /// Alongside the fragments, this returns `FeldmanCommitments` to the
+    /// coefficients of the sharing polynomial. The dealer publishes these to
+    /// every fragment holder, who can then check their fragment with
+    /// `verify_fragment` before using it.
+    pub fn split_verifiable(&self, m: usize, n: usize) -> (Vec<PrivateKey>, FeldmanCommitments) {
+        let coeffs = random_poly(&self.0, m);
+        (
+            fragments_from_poly(&coeffs[..], &default_ids(n)),
+            FeldmanCommitments::new(&coeffs[..]),
+        )
+    }
+
+    /// Verifies that a fragment is consistent with the `commitments` published
+    /// by the dealer in `split_verifiable`.
+    ///
+    /// Returns a `VerificationResult::Invalid` if this is not a fragment.
+    pub fn verify_fragment(&self, commitments: &FeldmanCommitments) -> VerificationResult {
+        match self.1 {
+            Some(fragment_index) => commitments.verify(&self.0, &fragment_index),
+            None => VerificationResult::Invalid,
+        }
+    }
+
+    /// Splits the private key into `n` fragments with a threshold of `m`, like
+    /// `ThresholdKey::split`, using Pedersen's Verifiable Secret Sharing.
+    ///
+    /// Alongside the fragments, this returns a `BlindingShare` for each
+    /// fragment and hiding `PedersenCommitments`, which, unlike
+    /// `FeldmanCommitments`, don't reveal the `PublicKey` of the split key.
+    /// Fragment holders check their fragment with `verify_pedersen_fragment`.
+    pub fn split_pedersen(
+        &self,
+        m: usize,
+        n: usize,
+    ) -> (Vec<PrivateKey>, Vec<BlindingShare>, PedersenCommitments) {
+        let coeffs = random_poly(&self.0, m);
+        let blinding_coeffs = random_poly(&PrivateKey::random().0, m);
+
+        let fragments = fragments_from_poly(&coeffs[..], &default_ids(n));
+        let blinding_shares = fragments
+            .iter()
+            .map(|fragment| BlindingShare(poly_eval(&blinding_coeffs[..], &fragment.1.unwrap())))
+            .collect();
+        (
+            fragments,
+            blinding_shares,
+            PedersenCommitments::new(&coeffs[..], &blinding_coeffs[..]),
+        )
+    }
+
+    /// Verifies that a fragment and its `blinding_share` are consistent with
+    /// the `commitments` published by the dealer in `split_pedersen`.
+    ///
+    /// Returns a `VerificationResult::Invalid` if this is not a fragment.
+    pub fn verify_pedersen_fragment(
+        &self,
+        blinding_share: &BlindingShare,
+        commitments: &PedersenCommitments,
+    ) -> VerificationResult {
+        match self.1 {
+            Some(fragment_index) => commitments.verify(&self.0, blinding_share, &fragment_index),
+            None => VerificationResult::Invalid,
+        }
+    }
+
+    /// Splits the private key into a fragment for each of the participant
+    /// `ids` with a threshold of `m`, like `ThresholdKey::split`.
+    ///
+    /// Each fragment's ID is the ID of the participant it is for, so that
+    /// fragments map to a roster of participants. Returns
+    /// `Error::InvalidFragmentIndex` if an ID is zero, and
+    /// `Error::DuplicateFragmentIndex` if one is repeated.
+    pub fn split_with_ids(&self, m: usize, ids: &[u32]) -> Result<Vec<PrivateKey>, Error> {
+        check_ids(ids)?;
+        let coeffs = random_poly(&self.0, m);
+        Ok(fragments_from_poly(&coeffs[..], ids))
+    }
+
+    /// Returns the fragment ID of a fragment, which is the ID of the
+    /// participant it was split for (see `split_with_ids`).
+    ///
+    /// Returns `None` if this is not a fragment, or if it is a fragment with
+    /// a random 32 byte index from an older version of NuBLS.
+    pub fn fragment_id(&self) -> Option<u32> {
+        self.1.as_ref().and_then(fragment_id)
+    }
+
+    /// Returns the length of the `PrivateKey` once serialized with `to_bytes`.
+    pub fn serialized_size(&self) -> usize {
+        SCALAR_BYTES_LENGTH + self.1.as_ref().map_or(0, fragment_index_length)
+    }
+
     /// Serializes the `PrivateKey` by filling a buffer passed as an argument.
-    /// If the buffer is not big enough, this method will panic.
+    /// If the buffer is shorter than `serialized_size`, this returns
+    /// `Error::InvalidLength`.
     ///
-    /// A `PrivateKey` can be serialized in two ways:
+    /// A `PrivateKey` can be serialized in three ways:
     ///  1. 32 bytes -- This is the case when a `PrivateKey` is _not_ being
-    ///  used for a threshold signature.
+    ///     used for a threshold signature.
     ///
-    ///  2. 64 bytes -- This is the case when a `PrivateKey` _is_ being used
-    ///  for a threshold signature. This allows us to store its fragment
-    ///  ID for Shamir's Secret Sharing.
+    ///  2. 36 bytes -- This is the case when a `PrivateKey` _is_ being used
+    ///     for a threshold signature. This allows us to store its fragment
+    ///     ID for Shamir's Secret Sharing, as a 4 byte big-endian integer.
     ///
-    /// Note: This serialization will probably change in the future.
-    /// See https://github.com/nucypher/NuBLS/issues/3
-    pub fn to_bytes(&self, buff: &mut [u8]) {
+    ///  3. 64 bytes -- This is the case for fragments with a random 32 byte
+    ///     index, as split by older versions of NuBLS.
+    pub fn to_bytes(&self, buff: &mut [u8]) -> Result<(), Error> {
+        let length = self.serialized_size();
+        if buff.len() < length {
+            return Err(Error::InvalidLength(buff.len()));
+        }
+
         buff[0..32].copy_from_slice(&self.0.to_bytes()[..]);
         if let Some(fragment_index) = self.1 {
-            buff[32..64].copy_from_slice(&fragment_index.to_bytes()[..]);
+            fragment_index_to_bytes(&fragment_index, &mut buff[32..length]);
         }
+        Ok(())
     }
 
     /// Deserializes from a `&[u8]` to a `PrivateKey`.
-    /// This will panic if the input is not canonical.
+    /// This returns an `Error` if the input is not 32, 36 or 64 bytes long,
+    /// or is not canonical.
     ///
-    /// A `PrivateKey` can be serialized in two ways:
+    /// A `PrivateKey` can be serialized in three ways:
     ///  1. 32 bytes -- This is the case when a `PrivateKey` is _not_ being
-    ///  used for a threshold signature.
-    ///
-    ///  2. 64 bytes -- This is the case when a `PrivateKey` _is_ being used
-    ///  for a threshold signature. This allows us to store its fragment
-    ///  ID for Shamir's Secret Sharing.
-    ///
-    /// Note: This serialization will probably change in the future.
-    /// See https://github.com/nucypher/NuBLS/issues/3
-    pub fn from_bytes(bytes: &[u8]) -> PrivateKey {
-        let mut scalar_bytes = [0u8; 32];
-        let fragment_index: Option<Scalar>;
-        if bytes.len() == SCALAR_BYTES_LENGTH {
-            scalar_bytes.copy_from_slice(&bytes);
-            fragment_index = None;
-        } else {
-            let mut index_bytes = [0u8; 32];
-            scalar_bytes.copy_from_slice(&bytes[0..SCALAR_BYTES_LENGTH]);
-            index_bytes.copy_from_slice(&bytes[SCALAR_BYTES_LENGTH..64]);
-            fragment_index = Some(Scalar::from_bytes(&index_bytes).unwrap());
-        }
-        PrivateKey(Scalar::from_bytes(&scalar_bytes).unwrap(), fragment_index)
+    ///     used for a threshold signature.
+    ///
+    ///  2. 36 bytes -- This is the case when a `PrivateKey` _is_ being used
+    ///     for a threshold signature. This allows us to store its fragment
+    ///     ID for Shamir's Secret Sharing, as a 4 byte big-endian integer.
+    ///
+    ///  3. 64 bytes -- This is the case for fragments with a random 32 byte
+    ///     index, as split by older versions of NuBLS.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PrivateKey, Error> {
+        let fragment_index = match bytes.len() {
+            SCALAR_BYTES_LENGTH => None,
+            36 | 64 => Some(fragment_index_from_bytes(&bytes[SCALAR_BYTES_LENGTH..])?),
+            length => return Err(Error::InvalidLength(length)),
+        };
+        Ok(PrivateKey(
+            scalar_from_bytes(&bytes[0..SCALAR_BYTES_LENGTH])?,
+            fragment_index,
+        ))
     }
 }
 
 impl PublicKey {
     /// Attempts to verify a signature given a `message_element` and a `signature`.
     ///
-    /// The `verify` API presently only works with messages already mapped to the
-    /// G_2 group on BLS12-381 (see https://github.com/nucypher/NuBLS/issues/1).
-    ///
-    /// TODO: Implement `hash_to_curve` per the IETF hash_to_curve specification.
+    /// The `verify` API works with messages already mapped to the G_2 group on
+    /// BLS12-381. To verify a signature over raw message bytes, use `verify_message`.
     pub fn verify(&self, message_element: &G2Affine, signature: &Signature) -> VerificationResult {
         signature.verify(self, message_element)
     }
 
-    /// Serializes the `PublicKey` to an array of 48 bytes.
-    pub fn to_bytes(&self) -> [u8; 48] {
-        self.0.to_compressed()
+    /// Attempts to verify a signature given the raw `message` bytes and a `signature`.
+    ///
+    /// This verifies under the `Ciphersuite::Basic` scheme, like `PrivateKey::sign_message`.
+    pub fn verify_message(&self, message: &[u8], signature: &Signature) -> VerificationResult {
+        Ciphersuite::Basic.verify(self, message, signature)
+    }
+
+    /// Attempts to verify a `proof` of possession of the `PublicKey`, as made
+    /// by `PrivateKey::prove_possession`.
+    ///
+    /// Returns a `VerificationResult::Invalid` if the `PublicKey` is the identity.
+    pub fn verify_possession(&self, proof: &Signature) -> VerificationResult {
+        if !key_validate(self) {
+            return VerificationResult::Invalid;
+        }
+        self.verify(&hash_to_g2(&self.0.to_compressed(), POP_TAG), proof)
     }
 
-    /// Deserializes from a `&[u8; 48]` to a `PublicKey`.
-    /// This will panic if the input is not valid.
-    pub fn from_bytes(bytes: &[u8; 48]) -> PublicKey {
-        PublicKey(G1Affine::from_compressed(bytes).unwrap())
+    /// Attempts to verify a signature fragment given a `message_element` and
+    /// a `signature`, where `self` is the `PublicKey` of a fragment.
+    ///
+    /// This lets whoever assembles a threshold signature discard invalid
+    /// fragments beforehand, since `ThresholdSignature::assemble` can't tell
+    /// them apart. Returns a `VerificationResult::Invalid` if either the
+    /// `PublicKey` or the `signature` is not a fragment, or if their
+    /// fragment IDs differ.
+    pub fn verify_fragment(
+        &self,
+        message_element: &G2Affine,
+        signature: &Signature,
+    ) -> VerificationResult {
+        match (self.1, signature.1) {
+            (Some(key_index), Some(sig_index)) if key_index == sig_index => {
+                signature.verify(self, message_element)
+            }
+            _ => VerificationResult::Invalid,
+        }
+    }
+
+    /// Attempts to verify a signature fragment given the raw `message` bytes
+    /// and a `signature`, like `verify_fragment`.
+    ///
+    /// This verifies under the `Ciphersuite::Basic` scheme, like `PrivateKey::sign_message`.
+    pub fn verify_fragment_message(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> VerificationResult {
+        self.verify_fragment(&hash_to_g2(message, Ciphersuite::Basic.id()), signature)
+    }
+
+    /// Aggregates the `public_keys` of independent signers into a single
+    /// `PublicKey` by summing them.
+    ///
+    /// Signatures from these signers over a common message verify under the
+    /// aggregate key once aggregated with `Signature::aggregate`.
+    ///
+    /// Note: Aggregating keys is only safe against rogue key attacks when
+    /// each key has a verified proof of possession.
+    pub fn aggregate(public_keys: &[PublicKey]) -> PublicKey {
+        let aggregate = public_keys
+            .iter()
+            .fold(G1Projective::identity(), |acc, public_key| {
+                acc + public_key.0
+            });
+        PublicKey(aggregate.into(), None)
+    }
+
+    /// Returns whether or not this is the `PublicKey` of a fragment of a key
+    /// used for threshold signatures.
+    pub fn is_fragment(&self) -> bool {
+        self.1.is_some()
+    }
+
+    /// Returns the fragment ID of the fragment this is the `PublicKey` of, as
+    /// in `PrivateKey::fragment_id`.
+    pub fn fragment_id(&self) -> Option<u32> {
+        self.1.as_ref().and_then(fragment_id)
+    }
+
+    /// Derives the public key of Bob's designated key for Alice from Alice's
+    /// `PublicKey` and the `resigning_key` from Bob to Alice, as
+    /// $\phi_B \cdot G = \frac{1}{\phi_{B \rightarrow A}} \cdot A$.
+    ///
+    /// This lets a proxy holding a re-signing key verify the signatures under
+    /// Bob's designated key that it's asked to re-sign. Returns
+    /// `Error::InvalidFragmentIndex` if the `resigning_key` is a fragment,
+    /// whose proxy must be given the designated public key instead, and
+    /// `Error::NonCanonicalScalar` if it's zero.
+    ///
+    /// Any re-signing key maps the key derived here to Alice's key, so it's
+    /// no evidence that the re-signing key is well-formed: check a
+    /// `ResigningKeyProof` against the designated public key from Bob instead.
+    pub fn designated_public_key(&self, resigning_key: &ResigningKey) -> Result<PublicKey, Error> {
+        if resigning_key.1.is_some() {
+            return Err(Error::InvalidFragmentIndex);
+        }
+        let inverse =
+            Option::<Scalar>::from(resigning_key.0.invert()).ok_or(Error::NonCanonicalScalar)?;
+        Ok(PublicKey((self.0 * inverse).into(), None))
+    }
+
+    /// Returns the length of the `PublicKey` once serialized with `to_bytes`.
+    pub fn serialized_size(&self) -> usize {
+        G1_POINT_BYTES_LENGTH + self.1.as_ref().map_or(0, fragment_index_length)
+    }
+
+    /// Serializes the `PublicKey` by filling a buffer passed as an argument.
+    /// If the buffer is shorter than `serialized_size`, this returns
+    /// `Error::InvalidLength`.
+    ///
+    /// A `PublicKey` can be serialized in three ways:
+    ///  1. 48 bytes -- This is the case when a `PublicKey` is _not_ the key
+    ///     of a fragment.
+    ///
+    ///  2. 52 bytes -- This is the case when a `PublicKey` _is_ the key of a
+    ///     fragment. This allows us to store its fragment ID for Shamir's
+    ///     Secret Sharing, as a 4 byte big-endian integer.
+    ///
+    ///  3. 80 bytes -- This is the case for the key of a fragment with a
+    ///     random 32 byte index, as split by older versions of NuBLS.
+    pub fn to_bytes(&self, buff: &mut [u8]) -> Result<(), Error> {
+        let length = self.serialized_size();
+        if buff.len() < length {
+            return Err(Error::InvalidLength(buff.len()));
+        }
+
+        buff[0..48].copy_from_slice(&self.0.to_compressed()[..]);
+        if let Some(fragment_index) = self.1 {
+            fragment_index_to_bytes(&fragment_index, &mut buff[48..length]);
+        }
+        Ok(())
+    }
+
+    /// Deserializes from a `&[u8]` to a `PublicKey`.
+    /// This returns an `Error` if the input is not 48, 52 or 80 bytes long,
+    /// or is not a valid, non-identity point in G_1.
+    ///
+    /// A `PublicKey` can be serialized in three ways:
+    ///  1. 48 bytes -- This is the case when a `PublicKey` is _not_ the key
+    ///     of a fragment.
+    ///
+    ///  2. 52 bytes -- This is the case when a `PublicKey` _is_ the key of a
+    ///     fragment. This allows us to store its fragment ID for Shamir's
+    ///     Secret Sharing, as a 4 byte big-endian integer.
+    ///
+    ///  3. 80 bytes -- This is the case for the key of a fragment with a
+    ///     random 32 byte index, as split by older versions of NuBLS.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, Error> {
+        let fragment_index = match bytes.len() {
+            G1_POINT_BYTES_LENGTH => None,
+            52 | 80 => Some(fragment_index_from_bytes(&bytes[G1_POINT_BYTES_LENGTH..])?),
+            length => return Err(Error::InvalidLength(length)),
+        };
+        let point = g1_from_bytes(&bytes[0..G1_POINT_BYTES_LENGTH])?;
+        if bool::from(point.is_identity()) {
+            return Err(Error::IdentityPoint);
+        }
+        Ok(PublicKey(point, fragment_index))
     }
 }
 
@@ -139,28 +418,16 @@ impl ThresholdKey for PrivateKey {
     /// re-assemble a secret. An attacker who knows `m-1` fragments knows just
     /// as much as an attacker who holds no shares due to the "perfect secrecy"
     /// of Shamir's Secret Sharing.
+    ///
+    /// The fragments are numbered with fragment IDs from `1` to `n`; use
+    /// `PrivateKey::split_with_ids` to pick the IDs.
     fn split(&self, m: usize, n: usize) -> Vec<PrivateKey> {
         // First, we randomly generate `m-1` coefficients to the polynomial.
         // Our secret is placed as the first term in the polynomial.
-        let mut coeffs = Vec::<Scalar>::with_capacity(m);
-        coeffs.push(self.0);
-        for _ in 1..m {
-            coeffs.push(PrivateKey::random().0);
-        }
+        let coeffs = random_poly(&self.0, m);
 
-        // Then we evaluate the polynomial `n` times using Horner's method and
-        // return the `collect`ed `Vector`.
-        // The index can be significantly improved, for more info see
-        // https://github.com/nucypher/NuBLS/issues/3.
-        let mut fragments = Vec::<PrivateKey>::with_capacity(n);
-        for _ in 0..n {
-            let fragment_index = PrivateKey::random().0;
-            fragments.push(PrivateKey(
-                poly_eval(&coeffs[..], &fragment_index),
-                Some(fragment_index),
-            ));
-        }
-        fragments
+        // Then we evaluate the polynomial `n` times.
+        fragments_from_poly(&coeffs[..], &default_ids(n))
     }
 
     /// Recovers a `PrivateKey` from the `fragments` provided by calculating
@@ -171,69 +438,147 @@ impl ThresholdKey for PrivateKey {
     /// "perfect secrecy" of Shamir's Secret Sharing, if `fragments` does not
     /// contain the threshold number of fragments (or the wrong fragments), then
     /// this will incorrectly recover the `PrivateKey` without warning.
-    fn recover(fragments: &[PrivateKey]) -> PrivateKey {
-        // First, we generate the fragment indices.
-        // We create a buffer to hold fragment indices of size 256 because
-        // our limit to fragments is 256.
-        // This can be significantly improved, for more info see
-        // https://github.com/nucypher/NuBLS/issues/3.
-        // TODO: https://github.com/nucypher/NuBLS/issues/25
-        let mut fragment_indices = [Scalar::zero(); 256];
-        for i in 0..fragments.len() {
-            fragment_indices[i] = fragments[i].1.unwrap();
-        }
+    fn recover(fragments: &[PrivateKey]) -> Result<PrivateKey, Error> {
+        // First, we collect and check the fragment indices.
+        let fragment_indices = fragment_indices(fragments.iter().map(|fragment| fragment.1))?;
 
         // Then we evaluate the Lagrange basis polynomials and return the
         // recovered `PrivateKey`.
-        // Note: we limit the `fragments_indices` slice to the length of the
-        // `fragments` slice.
         let mut result = Scalar::zero();
-        for fragment in fragments.iter() {
-            result += lambda_coeff(&fragment.1.unwrap(), &fragment_indices[..fragments.len()])
-                * fragment.0;
+        for (fragment, fragment_index) in fragments.iter().zip(fragment_indices.iter()) {
+            result += lambda_coeff(fragment_index, &fragment_indices[..]) * fragment.0;
         }
-        PrivateKey(result, None)
+        Ok(PrivateKey(result, None))
     }
 
-    /// Returns whether or not this is a fragment of a key used for 
+    /// Returns whether or not this is a fragment of a key used for
     /// threshold signatures.
     fn is_fragment(&self) -> bool {
-        match self.1 {
-            Some(_) => true,
-            None => false,
-        }
+        self.1.is_some()
     }
 }
 
+/// Evaluates the polynomial with `coeffs` at the fragment index of each of
+/// the `ids` using Horner's method and returns the resulting fragments.
+pub(crate) fn fragments_from_poly(coeffs: &[Scalar], ids: &[u32]) -> Vec<PrivateKey> {
+    ids.iter()
+        .map(|&id| {
+            let fragment_index = fragment_index(id);
+            PrivateKey(poly_eval(coeffs, &fragment_index), Some(fragment_index))
+        })
+        .collect()
+}
+
+/// Checks that the fragment `ids` are non-zero and distinct.
+pub(crate) fn check_ids(ids: &[u32]) -> Result<(), Error> {
+    fragment_indices(ids.iter().map(|&id| Some(fragment_index(id)))).map(|_| ())
+}
+
+/// Returns the fragment IDs `1..=n` that fragments are numbered with by default.
+fn default_ids(n: usize) -> Vec<u32> {
+    (1..=n as u32).collect()
+}
+
 impl PRSKey for PrivateKey {
     /// Calculates $\phi_{B \rightarrow A}$ as $\frac{a}{\phi_B}$
-    fn resigning_key(&self, bob_pubkey: &PublicKey) -> PrivateKey {
-        let phi_b = self.designated_key(&bob_pubkey).0;
-        PrivateKey(self.0 * (phi_b.invert().unwrap()), None)
+    fn resigning_key_with_kdf(
+        &self,
+        bob_pubkey: &PublicKey,
+        kdf: DesignatedKeyKdf,
+    ) -> ResigningKey {
+        let phi_b = self.designated_key_with_kdf(bob_pubkey, kdf).0;
+        ResigningKey(self.0 * (phi_b.invert().unwrap()), None)
     }
 
-    /// Calculate $\phi_B$ as a Diffie-Hellman between Alice and Bob.
-    fn designated_key(&self, alice_pubkey: &PublicKey) -> PrivateKey {
-        let dh = Sha512::digest(&G1Affine::from(alice_pubkey.0 * &self.0).to_uncompressed());
-        let mut scalar_bytes = [0u8; 64];
-        scalar_bytes.copy_from_slice(&dh.as_slice());
-        PrivateKey(Scalar::from_bytes_wide(&scalar_bytes), None)
+    /// Calculate $\phi_B$ as a Diffie-Hellman between Alice and Bob, with
+    /// the shared point and both of their public keys run through the `kdf`.
+    fn designated_key_with_kdf(
+        &self,
+        alice_pubkey: &PublicKey,
+        kdf: DesignatedKeyKdf,
+    ) -> DesignatedKey {
+        let shared_point = G1Affine::from(alice_pubkey.0 * self.0);
+        DesignatedKey(kdf.derive(&shared_point, alice_pubkey, &self.public_key()))
     }
 
-    /// Re-Signs a `Signature` from $\sigma_{\phi_B}$ to $\sigma_A$.
-    /// This is done by multiplying the `Signature` by the re-signing key:
-    /// $\sigma_A = \phi_{B \rightarrow A} \cdot \sigma_{\phi_B}$
-    ///
-    /// TODO: Validate signature before re-signing.
-    fn resign(&self, signature: &Signature) -> Signature {
-        Signature::new(&self, &signature.0)
+    /// Calculates $\phi_{C \rightarrow B}$ as $\frac{\phi_B}{\phi_C}$, where
+    /// $\phi_C$ is Carol's designated key for Bob, and $\phi_B$ is Bob's
+    /// designated key for Alice.
+    fn hop_resigning_key_with_kdf(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        kdf: DesignatedKeyKdf,
+    ) -> ResigningKey {
+        hop_resigning_key(
+            &self.designated_key_with_kdf(from_pubkey, kdf),
+            &self.designated_key_with_kdf(to_pubkey, kdf),
+        )
     }
+
+    /// Calculates $\phi_{C \rightarrow B}$ with $\phi_C$ for the `from_epoch`
+    /// and $\phi_B$ for the `to_epoch`.
+    fn hop_resigning_key_for_epoch(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        from_epoch: &[u8],
+        to_epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<ResigningKey, Error> {
+        Ok(hop_resigning_key(
+            &self.designated_key_for_epoch(from_pubkey, from_epoch, kdf)?,
+            &self.designated_key_for_epoch(to_pubkey, to_epoch, kdf)?,
+        ))
+    }
+
+    /// Calculates $\phi_{B \rightarrow A}$ with $\phi_B$ for the `epoch`.
+    fn resigning_key_for_epoch(
+        &self,
+        bob_pubkey: &PublicKey,
+        epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<ResigningKey, Error> {
+        let phi_b = self.designated_key_for_epoch(bob_pubkey, epoch, kdf)?.0;
+        Ok(ResigningKey(self.0 * (phi_b.invert().unwrap()), None))
+    }
+
+    /// Calculates $\phi_B$ with the `epoch` label bound into the HKDF info.
+    fn designated_key_for_epoch(
+        &self,
+        alice_pubkey: &PublicKey,
+        epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<DesignatedKey, Error> {
+        let shared_point = G1Affine::from(alice_pubkey.0 * self.0);
+        Ok(DesignatedKey(kdf.derive_for_epoch(
+            &shared_point,
+            alice_pubkey,
+            &self.public_key(),
+            epoch,
+        )?))
+    }
+
+    /// Calculates $\phi_B \cdot G$ from either side of the Diffie-Hellman.
+    fn designated_public_key(&self, counterparty_pubkey: &PublicKey) -> PublicKey {
+        self.designated_key(counterparty_pubkey).public_key()
+    }
+}
+
+/// Calculates the re-signing key of a hop from the designated key $\phi_C$ to
+/// the designated key $\phi_B$, as $\frac{\phi_B}{\phi_C}$.
+fn hop_resigning_key(phi_c: &DesignatedKey, phi_b: &DesignatedKey) -> ResigningKey {
+    let phi_c_inverse = Option::<Scalar>::from(phi_c.0.invert())
+        .expect("a designated key is a hash output, which is never zero in practice");
+    ResigningKey(phi_b.0 * phi_c_inverse, None)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kdf::DESIGNATED_KEY_DST;
     use crate::traits::ThresholdSignature;
+    use crate::utils::from_hex;
 
     #[test]
     fn test_random() {
@@ -259,7 +604,7 @@ mod tests {
 
         // Generate and sign a random message in G_2.
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
 
         let sig_msg = priv_a.sign(&msg);
         assert_eq!(sig_msg, Signature::new(&priv_a, &msg));
@@ -272,13 +617,88 @@ mod tests {
         // Generate a random invalid message for `sig_msg` and check that it
         // is invalid.
         let new_rand = PrivateKey::random();
-        let bad_msg = G2Affine::from(G2Affine::generator() * &new_rand.0);
+        let bad_msg = G2Affine::from(G2Affine::generator() * new_rand.0);
         assert_ne!(bad_msg, msg);
 
         let not_verified = pub_a.verify(&bad_msg, &sig_msg);
         assert_eq!(not_verified, VerificationResult::Invalid);
     }
 
+    #[test]
+    fn test_signing_and_verifying_messages() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+
+        // Sign a message without pre-mapping it to G_2.
+        let sig_msg = priv_a.sign_message(b"NuBLS!");
+        assert_eq!(
+            sig_msg,
+            priv_a.sign(&hash_to_g2(b"NuBLS!", Ciphersuite::Basic.id()))
+        );
+        assert_eq!(
+            pub_a.verify_message(b"NuBLS!", &sig_msg),
+            VerificationResult::Valid
+        );
+
+        // A different message, or the wrong key, must not verify.
+        assert_eq!(
+            pub_a.verify_message(b"Penumbral!", &sig_msg),
+            VerificationResult::Invalid
+        );
+        let pub_b = PrivateKey::random().public_key();
+        assert_eq!(
+            pub_b.verify_message(b"NuBLS!", &sig_msg),
+            VerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+        let pub_b = PrivateKey::random().public_key();
+
+        let proof_a = priv_a.prove_possession();
+        assert_eq!(pub_a.verify_possession(&proof_a), VerificationResult::Valid);
+        assert_eq!(
+            pub_b.verify_possession(&proof_a),
+            VerificationResult::Invalid
+        );
+
+        // A proof is not a signature on the public key under a signing DST,
+        // and vice versa.
+        let pub_a_bytes = pub_a.0.to_compressed();
+        let sig_pub_a = Ciphersuite::ProofOfPossession.sign(&priv_a, &pub_a_bytes);
+        assert_ne!(sig_pub_a, proof_a);
+        assert_eq!(
+            pub_a.verify_possession(&sig_pub_a),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            Ciphersuite::ProofOfPossession.verify(&pub_a, &pub_a_bytes, &proof_a),
+            VerificationResult::Invalid
+        );
+
+        // A rogue key `pub_b - pub_a` can't be proven without its private key.
+        let rogue = PublicKey(
+            (G1Projective::from(pub_b.0) - G1Projective::from(pub_a.0)).into(),
+            None,
+        );
+        assert_eq!(
+            rogue.verify_possession(&proof_a),
+            VerificationResult::Invalid
+        );
+
+        // Fragments prove possession of their own share.
+        let frag = priv_a.split(3, 5)[0];
+        let frag_proof = frag.prove_possession();
+        assert!(!frag_proof.is_fragment());
+        assert_eq!(
+            frag.public_key().verify_possession(&frag_proof),
+            VerificationResult::Valid
+        );
+    }
+
     #[test]
     fn test_verification_result_handling() {
         // This test demonstrates the misuse-resistant signature verification
@@ -289,7 +709,7 @@ mod tests {
 
         // Generate and sign a random message to sign in G_2.
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
         let sig_msg = priv_a.sign(&msg);
 
         // We define a function that handles the logic of a signature verification
@@ -311,7 +731,7 @@ mod tests {
 
         // Let's try an invalid signature
         let new_rand = PrivateKey::random();
-        let bad_msg = G2Affine::from(G2Affine::generator() * &new_rand.0);
+        let bad_msg = G2Affine::from(G2Affine::generator() * new_rand.0);
         let not_verified = pub_a.verify(&bad_msg, &sig_msg);
         assert_eq!(
             "Invalid message!",
@@ -325,7 +745,7 @@ mod tests {
         let n_frags = priv_a.split(3, 5);
         let m_frags = &n_frags[0..3];
 
-        let recovered_a = PrivateKey::recover(&m_frags);
+        let recovered_a = PrivateKey::recover(m_frags).unwrap();
         assert_eq!(recovered_a, priv_a);
     }
 
@@ -335,16 +755,140 @@ mod tests {
         let n_frags = priv_a.split(3, 5);
 
         let mut a_bytes = [0u8; 32];
-        let mut frag_bytes = [0u8; 64];
-        priv_a.to_bytes(&mut a_bytes);
-        n_frags[0].to_bytes(&mut frag_bytes);
+        let mut frag_bytes = [0u8; 36];
+        priv_a.to_bytes(&mut a_bytes).unwrap();
+        n_frags[0].to_bytes(&mut frag_bytes).unwrap();
 
-        assert_eq!(a_bytes.len(), 32);
-        assert_eq!(frag_bytes.len(), 64);
+        assert_eq!(priv_a.serialized_size(), 32);
+        assert_eq!(n_frags[0].serialized_size(), 36);
         assert_ne!(a_bytes[..32], frag_bytes[..32]);
+        assert_eq!(frag_bytes[32..], [0, 0, 0, 1]);
+
+        assert_eq!(PrivateKey::from_bytes(&a_bytes), Ok(priv_a));
+        assert_eq!(PrivateKey::from_bytes(&frag_bytes), Ok(n_frags[0]));
 
-        assert_eq!(PrivateKey::from_bytes(&a_bytes), priv_a);
-        assert_eq!(PrivateKey::from_bytes(&frag_bytes), n_frags[0]);
+        let pub_a = priv_a.public_key();
+        let pub_frag = n_frags[0].public_key();
+        let mut pub_a_bytes = [0u8; 48];
+        let mut pub_frag_bytes = [0u8; 52];
+        pub_a.to_bytes(&mut pub_a_bytes).unwrap();
+        pub_frag.to_bytes(&mut pub_frag_bytes).unwrap();
+        assert_eq!(pub_frag.serialized_size(), 52);
+        assert_eq!(PublicKey::from_bytes(&pub_a_bytes), Ok(pub_a));
+        assert_eq!(PublicKey::from_bytes(&pub_frag_bytes), Ok(pub_frag));
+
+        // Fragments with a random 32 byte index, as split by older versions,
+        // still round-trip.
+        let legacy_frag = PrivateKey(n_frags[0].0, Some(PrivateKey::random().0));
+        let mut legacy_bytes = [0u8; 64];
+        legacy_frag.to_bytes(&mut legacy_bytes).unwrap();
+        assert_eq!(legacy_frag.serialized_size(), 64);
+        assert_eq!(legacy_frag.fragment_id(), None);
+        assert_eq!(PrivateKey::from_bytes(&legacy_bytes), Ok(legacy_frag));
+
+        let legacy_pub = legacy_frag.public_key();
+        let mut legacy_pub_bytes = [0u8; 80];
+        legacy_pub.to_bytes(&mut legacy_pub_bytes).unwrap();
+        assert_eq!(PublicKey::from_bytes(&legacy_pub_bytes), Ok(legacy_pub));
+    }
+
+    #[test]
+    fn test_fragment_ids() {
+        let priv_a = PrivateKey::random();
+        let n_frags = priv_a.split(3, 5);
+        let ids = n_frags
+            .iter()
+            .map(|fragment| fragment.fragment_id().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(priv_a.fragment_id(), None);
+
+        // Fragments can be split for a roster of participant IDs instead.
+        let roster = [7, 42, 1000, 3];
+        let roster_frags = priv_a.split_with_ids(2, &roster).unwrap();
+        for (fragment, id) in roster_frags.iter().zip(roster.iter()) {
+            assert_eq!(fragment.fragment_id(), Some(*id));
+            assert_eq!(fragment.public_key().fragment_id(), Some(*id));
+            assert_eq!(fragment.sign_message(b"NuBLS!").fragment_id(), Some(*id));
+        }
+        assert_eq!(PrivateKey::recover(&roster_frags[1..3]).unwrap(), priv_a);
+
+        assert_eq!(
+            priv_a.split_with_ids(2, &[1, 0, 2]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(
+            priv_a.split_with_ids(2, &[1, 2, 1]),
+            Err(Error::DuplicateFragmentIndex)
+        );
+    }
+
+    #[test]
+    fn test_key_deserialization_errors() {
+        let priv_a = PrivateKey::random();
+        let n_frags = priv_a.split(3, 5);
+
+        // Buffers that are too short are rejected rather than panicking.
+        let mut short_bytes = [0u8; 32];
+        assert_eq!(
+            n_frags[0].to_bytes(&mut short_bytes),
+            Err(Error::InvalidLength(32))
+        );
+        assert_eq!(
+            priv_a.to_bytes(&mut short_bytes[..31]),
+            Err(Error::InvalidLength(31))
+        );
+
+        assert_eq!(
+            PrivateKey::from_bytes(&[0u8; 33]),
+            Err(Error::InvalidLength(33))
+        );
+        assert_eq!(
+            PrivateKey::from_bytes(&[0xffu8; 32]),
+            Err(Error::NonCanonicalScalar)
+        );
+
+        let mut frag_bytes = [0u8; 64];
+        n_frags[0].to_bytes(&mut frag_bytes).unwrap();
+        frag_bytes[32..].copy_from_slice(&[0u8; 32]);
+        assert_eq!(
+            PrivateKey::from_bytes(&frag_bytes),
+            Err(Error::InvalidFragmentIndex)
+        );
+        frag_bytes[32..].copy_from_slice(&[0xffu8; 32]);
+        assert_eq!(
+            PrivateKey::from_bytes(&frag_bytes),
+            Err(Error::InvalidFragmentIndex)
+        );
+
+        assert_eq!(
+            PublicKey::from_bytes(&[0u8; 47]),
+            Err(Error::InvalidLength(47))
+        );
+        let mut short_bytes = [0u8; 48];
+        assert_eq!(
+            n_frags[0].public_key().to_bytes(&mut short_bytes),
+            Err(Error::InvalidLength(48))
+        );
+
+        // x = 1 is not the x-coordinate of any point on the curve, and
+        // x = 4 is the x-coordinate of a point outside of G_1.
+        let mut point_bytes = [0u8; 48];
+        point_bytes[0] = 0x80;
+        point_bytes[47] = 1;
+        assert_eq!(
+            PublicKey::from_bytes(&point_bytes),
+            Err(Error::PointNotOnCurve)
+        );
+        point_bytes[47] = 4;
+        assert_eq!(
+            PublicKey::from_bytes(&point_bytes),
+            Err(Error::PointNotInSubgroup)
+        );
+        assert_eq!(
+            PublicKey::from_bytes(&G1Affine::identity().to_compressed()),
+            Err(Error::IdentityPoint)
+        );
     }
 
     #[test]
@@ -353,21 +897,71 @@ mod tests {
         let n_frags = priv_a.split(3, 5);
 
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
         let sig = priv_a.sign(&msg);
         let frag_sig = n_frags[0].sign(&msg);
 
         let mut sig_bytes = [0u8; 96];
-        let mut frag_sig_bytes = [0u8; 128];
-        sig.to_bytes(&mut sig_bytes);
-        frag_sig.to_bytes(&mut frag_sig_bytes);
+        let mut frag_sig_bytes = [0u8; 100];
+        sig.to_bytes(&mut sig_bytes).unwrap();
+        frag_sig.to_bytes(&mut frag_sig_bytes).unwrap();
 
-        assert_eq!(sig_bytes.len(), 96);
-        assert_eq!(frag_sig_bytes.len(), 128);
+        assert_eq!(sig.serialized_size(), 96);
+        assert_eq!(frag_sig.serialized_size(), 100);
         assert_ne!(sig_bytes[..96], frag_sig_bytes[..96]);
 
-        assert_eq!(Signature::from_bytes(&sig_bytes), sig);
-        assert_eq!(Signature::from_bytes(&frag_sig_bytes), frag_sig);
+        assert_eq!(Signature::from_bytes(&sig_bytes), Ok(sig));
+        assert_eq!(Signature::from_bytes(&frag_sig_bytes), Ok(frag_sig));
+
+        let legacy_frag_sig = Signature(frag_sig.0, Some(PrivateKey::random().0));
+        let mut legacy_bytes = [0u8; 128];
+        legacy_frag_sig.to_bytes(&mut legacy_bytes).unwrap();
+        assert_eq!(Signature::from_bytes(&legacy_bytes), Ok(legacy_frag_sig));
+    }
+
+    #[test]
+    fn test_signature_deserialization_errors() {
+        let priv_a = PrivateKey::random();
+        let n_frags = priv_a.split(3, 5);
+        let frag_sig = n_frags[0].sign_message(b"NuBLS!");
+
+        let mut short_bytes = [0u8; 96];
+        assert_eq!(
+            frag_sig.to_bytes(&mut short_bytes),
+            Err(Error::InvalidLength(96))
+        );
+
+        assert_eq!(
+            Signature::from_bytes(&[0u8; 97]),
+            Err(Error::InvalidLength(97))
+        );
+
+        let mut frag_sig_bytes = [0u8; 128];
+        frag_sig.to_bytes(&mut frag_sig_bytes).unwrap();
+        frag_sig_bytes[96..].copy_from_slice(&[0u8; 32]);
+        assert_eq!(
+            Signature::from_bytes(&frag_sig_bytes),
+            Err(Error::InvalidFragmentIndex)
+        );
+
+        // x = 1 is not the x-coordinate of any point on the curve, and
+        // x = 2 is the x-coordinate of a point outside of G_2.
+        let mut point_bytes = [0u8; 96];
+        point_bytes[0] = 0x80;
+        point_bytes[95] = 1;
+        assert_eq!(
+            Signature::from_bytes(&point_bytes),
+            Err(Error::PointNotOnCurve)
+        );
+        point_bytes[95] = 2;
+        assert_eq!(
+            Signature::from_bytes(&point_bytes),
+            Err(Error::PointNotInSubgroup)
+        );
+        assert_eq!(
+            Signature::from_bytes(&G2Affine::identity().to_compressed()),
+            Err(Error::IdentityPoint)
+        );
     }
 
     #[test]
@@ -375,15 +969,15 @@ mod tests {
         // Testing `PrivateKey`
         let priv_a = PrivateKey::random();
         let n_frags = priv_a.split(3, 5);
-        assert_eq!(n_frags[0].is_fragment(), true);
-        assert_eq!(priv_a.is_fragment(), false);
+        assert!(n_frags[0].is_fragment());
+        assert!(!priv_a.is_fragment());
 
         // Testing `Signature`
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
         let sig = n_frags[0].sign(&msg);
-        assert_eq!(sig.is_fragment(), true);
-        assert_eq!(priv_a.sign(&msg).is_fragment(), false);
+        assert!(sig.is_fragment());
+        assert!(!priv_a.sign(&msg).is_fragment());
     }
 
     #[test]
@@ -393,10 +987,69 @@ mod tests {
 
         // Select m fragments under the threshold and attempt recovery
         let m_frags = &n_frags[0..2];
-        let bad_recovery = PrivateKey::recover(&m_frags);
+        let bad_recovery = PrivateKey::recover(m_frags).unwrap();
         assert_ne!(bad_recovery, priv_a);
     }
 
+    #[test]
+    fn test_more_than_256_fragments() {
+        let priv_a = PrivateKey::random();
+        let n_frags = priv_a.split(300, 300);
+        assert_eq!(PrivateKey::recover(&n_frags[..]), Ok(priv_a));
+
+        let sig_frags = n_frags
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Signature::assemble(&sig_frags[..]),
+            Ok(priv_a.sign_message(b"NuBLS!"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fragment_sets() {
+        let priv_a = PrivateKey::random();
+        let n_frags = priv_a.split(3, 5);
+        let sig_frags = n_frags
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+
+        // Repeated fragments would divide by zero in the Lagrange basis.
+        let repeated = [n_frags[0], n_frags[1], n_frags[0]];
+        assert_eq!(
+            PrivateKey::recover(&repeated),
+            Err(Error::DuplicateFragmentIndex)
+        );
+        let repeated_sigs = [sig_frags[0], sig_frags[1], sig_frags[0]];
+        assert_eq!(
+            Signature::assemble(&repeated_sigs),
+            Err(Error::DuplicateFragmentIndex)
+        );
+
+        // So would a zero fragment index, and a full key has no index.
+        let zero = [
+            n_frags[0],
+            n_frags[1],
+            PrivateKey(n_frags[2].0, Some(Scalar::zero())),
+        ];
+        assert_eq!(PrivateKey::recover(&zero), Err(Error::InvalidFragmentIndex));
+        let unsplit = [n_frags[0], n_frags[1], priv_a];
+        assert_eq!(
+            PrivateKey::recover(&unsplit),
+            Err(Error::InvalidFragmentIndex)
+        );
+        let unsplit_sigs = [sig_frags[0], sig_frags[1], priv_a.sign_message(b"NuBLS!")];
+        assert_eq!(
+            Signature::assemble(&unsplit_sigs),
+            Err(Error::InvalidFragmentIndex)
+        );
+
+        assert_eq!(PrivateKey::recover(&[]), Err(Error::NotEnoughFragments));
+        assert_eq!(Signature::assemble(&[]), Err(Error::NotEnoughFragments));
+    }
+
     #[test]
     fn test_threshold_signature_3_of_5() {
         // Split the private key into five fragments, one for each Signer.
@@ -405,7 +1058,7 @@ mod tests {
 
         // Generate a random message in G_2
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
 
         // Get three signatures on the `msg` from each Signer
         let sig_1 = n_frags[0].sign(&msg);
@@ -413,8 +1066,8 @@ mod tests {
         let sig_3 = n_frags[3].sign(&msg);
 
         // Place them into a vector and assemble the full signature
-        let sig_frags = vec![sig_1, sig_2, sig_3];
-        let full_sig = Signature::assemble(&sig_frags[..]);
+        let sig_frags = [sig_1, sig_2, sig_3];
+        let full_sig = Signature::assemble(&sig_frags[..]).unwrap();
 
         // Sign the same data with the unsplit key to verify correctness
         // BLS is a deterministic signature, so we can simply check that the
@@ -427,13 +1080,145 @@ mod tests {
         assert_eq!(pub_a.verify(&msg, &full_sig), VerificationResult::Valid);
     }
 
+    #[test]
+    fn test_signature_fragment_verification() {
+        let priv_a = PrivateKey::random();
+        let (n_frags, commitments) = priv_a.split_verifiable(3, 5);
+        let sig_frags = n_frags
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+
+        // Each fragment's `PublicKey` can be derived from the fragment, or
+        // from the dealer's commitments.
+        let frag_keys = n_frags
+            .iter()
+            .map(|fragment| commitments.fragment_public_key(&fragment.1.unwrap()))
+            .collect::<Vec<_>>();
+        for (fragment, frag_key) in n_frags.iter().zip(frag_keys.iter()) {
+            assert_eq!(&fragment.public_key(), frag_key);
+            assert!(frag_key.is_fragment());
+        }
+        assert!(!priv_a.public_key().is_fragment());
+
+        for (sig_frag, frag_key) in sig_frags.iter().zip(frag_keys.iter()) {
+            assert_eq!(
+                frag_key.verify_fragment_message(b"NuBLS!", sig_frag),
+                VerificationResult::Valid
+            );
+        }
+
+        // A fragment doesn't verify under another fragment's key, for another
+        // message, or if it isn't a fragment at all.
+        assert_eq!(
+            frag_keys[1].verify_fragment_message(b"NuBLS!", &sig_frags[0]),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            frag_keys[0].verify_fragment_message(b"Penumbral!", &sig_frags[0]),
+            VerificationResult::Invalid
+        );
+        let relabeled = Signature(sig_frags[0].0, sig_frags[1].1);
+        assert_eq!(
+            frag_keys[1].verify_fragment_message(b"NuBLS!", &relabeled),
+            VerificationResult::Invalid
+        );
+        let full_sig = priv_a.sign_message(b"NuBLS!");
+        assert_eq!(
+            priv_a
+                .public_key()
+                .verify_fragment_message(b"NuBLS!", &full_sig),
+            VerificationResult::Invalid
+        );
+
+        // Filtering out a corrupted fragment before assembly leaves a valid
+        // signature.
+        let mut received = sig_frags.clone();
+        received[0] = n_frags[1].sign_message(b"NuBLS!");
+        let valid_frags = received
+            .iter()
+            .zip(frag_keys.iter())
+            .filter(|(sig_frag, frag_key)| {
+                frag_key.verify_fragment_message(b"NuBLS!", sig_frag) == VerificationResult::Valid
+            })
+            .map(|(sig_frag, _)| *sig_frag)
+            .collect::<Vec<_>>();
+        assert_eq!(valid_frags.len(), 4);
+        assert_eq!(Signature::assemble(&valid_frags[..3]).unwrap(), full_sig);
+    }
+
+    #[test]
+    fn test_robust_signature_assembly() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+        let (n_frags, commitments) = priv_a.split_verifiable(3, 6);
+        let frag_keys = n_frags
+            .iter()
+            .map(|fragment| commitments.fragment_public_key(&fragment.1.unwrap()))
+            .collect::<Vec<_>>();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+        let full_sig = priv_a.sign(&msg);
+
+        // Fragment 1 signs the wrong message, fragment 3 claims the wrong
+        // fragment ID, and fragment 4 isn't a fragment at all.
+        let mut sig_frags = n_frags
+            .iter()
+            .map(|fragment| fragment.sign(&msg))
+            .collect::<Vec<_>>();
+        sig_frags[1] = n_frags[1].sign(&G2Affine::generator());
+        sig_frags[3].1 = n_frags[5].1;
+        sig_frags[4] = priv_a.sign(&msg);
+
+        assert_eq!(
+            Signature::assemble_robust(&sig_frags[..], 3, &pub_a, &msg, None),
+            Ok((full_sig, vec![1, 3, 4]))
+        );
+        assert_eq!(
+            Signature::assemble_robust(&sig_frags[..], 3, &pub_a, &msg, Some(&frag_keys[..])),
+            Ok((full_sig, vec![1, 3, 4]))
+        );
+
+        // A valid copy of a fragment isn't faulty.
+        sig_frags.push(sig_frags[0]);
+        assert_eq!(
+            Signature::assemble_robust(&sig_frags[..], 3, &pub_a, &msg, None),
+            Ok((full_sig, vec![1, 3, 4]))
+        );
+
+        // With only two valid fragments left, no signature can be assembled.
+        sig_frags[2] = n_frags[2].sign(&G2Affine::generator());
+        assert_eq!(
+            Signature::assemble_robust(&sig_frags[..], 3, &pub_a, &msg, None),
+            Err(Error::NotEnoughFragments)
+        );
+        assert_eq!(
+            Signature::assemble_robust(&sig_frags[..], 3, &pub_a, &msg, Some(&frag_keys[..])),
+            Err(Error::NotEnoughFragments)
+        );
+        assert_eq!(
+            Signature::assemble_robust(&[], 0, &pub_a, &msg, None),
+            Err(Error::NotEnoughFragments)
+        );
+
+        // Fragments that all claim the same fragment ID never assemble, and
+        // the search gives up after `MAX_ROBUST_ASSEMBLY_SUBSETS` of the
+        // C(40, 20) subsets rather than trying them all.
+        let spam = vec![sig_frags[0]; 40];
+        assert_eq!(
+            Signature::assemble_robust(&spam[..], 20, &pub_a, &msg, None),
+            Err(Error::NotEnoughFragments)
+        );
+    }
+
     #[test]
     fn test_unordered_index_key_recovery() {
         let priv_a = PrivateKey::random();
         let n_frags = priv_a.split(3, 5);
         let m_frags = &n_frags[2..5];
 
-        let recovered_a = PrivateKey::recover(&m_frags);
+        let recovered_a = PrivateKey::recover(m_frags).unwrap();
         assert_eq!(recovered_a, priv_a);
     }
 
@@ -443,14 +1228,14 @@ mod tests {
         let n_frags = priv_a.split(3, 5);
 
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
 
         let sig_1 = n_frags[0].sign(&msg);
         let sig_2 = n_frags[1].sign(&msg);
         let sig_3 = n_frags[3].sign(&msg);
 
-        let sig_frags = vec![sig_1, sig_2, sig_3];
-        let full_sig = Signature::assemble(&sig_frags[..]);
+        let sig_frags = [sig_1, sig_2, sig_3];
+        let full_sig = Signature::assemble(&sig_frags[..]).unwrap();
 
         // Check that the signature verifies
         let pub_a = priv_a.public_key();
@@ -465,15 +1250,15 @@ mod tests {
 
         // Generate a random message in G_2
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
 
         // Get two signatures on the `msg`; under the threshold
         let sig_1 = n_frags[0].sign(&msg);
         let sig_2 = n_frags[1].sign(&msg);
 
         // Place them into a vector and assemble an incomplete signature
-        let sig_frags = vec![sig_1, sig_2];
-        let bad_sig = Signature::assemble(&sig_frags[..]);
+        let sig_frags = [sig_1, sig_2];
+        let bad_sig = Signature::assemble(&sig_frags[..]).unwrap();
 
         // Sign the same data with the unsplit key to verify correctness
         // BLS is a deterministic signature, so we can simply check that the
@@ -482,6 +1267,108 @@ mod tests {
         assert_ne!(msg_sig, bad_sig);
     }
 
+    #[test]
+    fn test_signature_aggregation() {
+        let priv_keys = [
+            PrivateKey::random(),
+            PrivateKey::random(),
+            PrivateKey::random(),
+        ];
+        let pub_keys = [
+            priv_keys[0].public_key(),
+            priv_keys[1].public_key(),
+            priv_keys[2].public_key(),
+        ];
+
+        // Each signer signs a different random message in G_2.
+        let msgs = [
+            G2Affine::from(G2Affine::generator() * PrivateKey::random().0),
+            G2Affine::from(G2Affine::generator() * PrivateKey::random().0),
+            G2Affine::from(G2Affine::generator() * PrivateKey::random().0),
+        ];
+        let sigs = [
+            priv_keys[0].sign(&msgs[0]),
+            priv_keys[1].sign(&msgs[1]),
+            priv_keys[2].sign(&msgs[2]),
+        ];
+
+        let agg_sig = Signature::aggregate(&sigs);
+        assert!(!agg_sig.is_fragment());
+        assert_eq!(
+            agg_sig.aggregate_verify(&pub_keys, &msgs),
+            VerificationResult::Valid
+        );
+
+        // Dropping a signer, mixing up messages, or passing mismatched
+        // inputs must not verify.
+        assert_eq!(
+            agg_sig.aggregate_verify(&pub_keys[..2], &msgs[..2]),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            agg_sig.aggregate_verify(&pub_keys, &[msgs[1], msgs[0], msgs[2]]),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            agg_sig.aggregate_verify(&pub_keys, &msgs[..2]),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            Signature::aggregate(&[]).aggregate_verify(&[], &[]),
+            VerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_fast_aggregate_verify() {
+        let priv_keys = [
+            PrivateKey::random(),
+            PrivateKey::random(),
+            PrivateKey::random(),
+        ];
+        let pub_keys = [
+            priv_keys[0].public_key(),
+            priv_keys[1].public_key(),
+            priv_keys[2].public_key(),
+        ];
+
+        // Every signer signs the same message.
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+        let agg_sig = Signature::aggregate(&[
+            priv_keys[0].sign(&msg),
+            priv_keys[1].sign(&msg),
+            priv_keys[2].sign(&msg),
+        ]);
+
+        assert_eq!(
+            agg_sig.fast_aggregate_verify(&pub_keys, &msg),
+            VerificationResult::Valid
+        );
+        assert_eq!(
+            agg_sig.aggregate_verify(&pub_keys, &[msg, msg, msg]),
+            VerificationResult::Valid
+        );
+
+        // The aggregate signature is a plain signature under the aggregate key.
+        let agg_key = PublicKey::aggregate(&pub_keys);
+        assert_eq!(agg_key.verify(&msg, &agg_sig), VerificationResult::Valid);
+
+        let bad_msg = G2Affine::from(G2Affine::generator() * PrivateKey::random().0);
+        assert_eq!(
+            agg_sig.fast_aggregate_verify(&pub_keys, &bad_msg),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            agg_sig.fast_aggregate_verify(&pub_keys[..2], &msg),
+            VerificationResult::Invalid
+        );
+        assert_eq!(
+            agg_sig.fast_aggregate_verify(&[], &msg),
+            VerificationResult::Invalid
+        );
+    }
+
     #[test]
     fn test_proxy_re_signature() {
         let priv_alice = PrivateKey::random();
@@ -492,7 +1379,7 @@ mod tests {
 
         // Generate a random message in G_2 to sign
         let rand = PrivateKey::random();
-        let msg = G2Affine::from(G2Affine::generator() * &rand.0);
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
 
         // Alice grants re-signing capabilities to Bob by generating a
         // resigning key that transforms signatures from Bob's designated key
@@ -513,4 +1400,298 @@ mod tests {
         assert_eq!(sig_a, priv_alice.sign(&msg));
         assert_eq!(pub_alice.verify(&msg, &sig_a), VerificationResult::Valid);
     }
+
+    #[test]
+    fn test_default_kdf_matches_earlier_versions() {
+        // Keys derived by NuBLS before designated keys could be derived with
+        // HKDF, which the default methods must keep deriving.
+        let priv_alice = PrivateKey::from_bytes(&from_hex(
+            "eff997f32a0d5d2c526fb64291bae89d1385330424de1ce237cf0976070fc854",
+        ))
+        .unwrap();
+        let priv_bob = PrivateKey::from_bytes(&from_hex(
+            "3a0d06f9bf33cf1c2aa4a8fbf856f1d61b1789fae1aa17742e0ec372c302d612",
+        ))
+        .unwrap();
+
+        let designated_key = priv_bob.designated_key(&priv_alice.public_key());
+        assert_eq!(
+            designated_key.0.to_bytes().to_vec(),
+            from_hex("7be328d1ef153c936c390ad06a3db64d554be463f398cda2545afec012ce6b46")
+        );
+        let resigning_key = priv_alice.resigning_key(&priv_bob.public_key());
+        assert_eq!(
+            resigning_key.0.to_bytes().to_vec(),
+            from_hex("b5e684cdeea7043e0d548607fe9f0ee2eb74943bc05d5f0fe7db3c98d1320440")
+        );
+    }
+
+    #[test]
+    fn test_proxy_re_signature_kdfs() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // Re-signing works under every KDF, as long as both sides agree.
+        let kdfs = [
+            DesignatedKeyKdf::HkdfSha256(DESIGNATED_KEY_DST),
+            DesignatedKeyKdf::HkdfSha256(b"NUBLS_TEST_APP_"),
+            DesignatedKeyKdf::Legacy,
+        ];
+        for kdf in kdfs.iter() {
+            let rekey_ab = priv_alice.resigning_key_with_kdf(&pub_bob, *kdf);
+            let sig_b = priv_bob
+                .designated_key_with_kdf(&pub_alice, *kdf)
+                .sign(&msg);
+            assert_eq!(rekey_ab.resign(&sig_b), priv_alice.sign(&msg));
+        }
+        assert_eq!(
+            priv_bob.designated_key(&pub_alice),
+            priv_bob.designated_key_with_kdf(&pub_alice, DesignatedKeyKdf::default())
+        );
+
+        // Designated keys under one KDF can't be re-signed with another.
+        let rekey_ab = priv_alice
+            .resigning_key_with_kdf(&pub_bob, DesignatedKeyKdf::HkdfSha256(DESIGNATED_KEY_DST));
+        let legacy_sig_b = priv_bob
+            .designated_key_with_kdf(&pub_alice, DesignatedKeyKdf::Legacy)
+            .sign(&msg);
+        assert_ne!(rekey_ab.resign(&legacy_sig_b), priv_alice.sign(&msg));
+    }
+
+    #[test]
+    fn test_epoch_re_signature() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // Alice issues a re-signing key for the first epoch.
+        let kdf = DesignatedKeyKdf::HkdfSha256(DESIGNATED_KEY_DST);
+        let rekey_1 = priv_alice
+            .resigning_key_for_epoch(&pub_bob, b"epoch-1", kdf)
+            .unwrap();
+        let designated_1 = priv_bob
+            .designated_key_for_epoch(&pub_alice, b"epoch-1", kdf)
+            .unwrap();
+        let sig_b_1 = designated_1.sign(&msg);
+        assert_eq!(rekey_1.resign(&sig_b_1), priv_alice.sign(&msg));
+        assert_ne!(designated_1, priv_bob.designated_key(&pub_alice));
+
+        // She revokes it by moving Bob to the second epoch: the old re-signing
+        // key no longer re-signs Bob's signatures, and its proxy rejects them.
+        let rekey_2 = priv_alice
+            .resigning_key_for_epoch(&pub_bob, b"epoch-2", kdf)
+            .unwrap();
+        let sig_b_2 = priv_bob
+            .designated_key_for_epoch(&pub_alice, b"epoch-2", kdf)
+            .unwrap()
+            .sign(&msg);
+        assert_eq!(rekey_2.resign(&sig_b_2), priv_alice.sign(&msg));
+        assert_ne!(rekey_1.resign(&sig_b_2), priv_alice.sign(&msg));
+        let pub_designated_1 = pub_alice.designated_public_key(&rekey_1).unwrap();
+        assert_eq!(pub_designated_1, designated_1.public_key());
+        assert_eq!(
+            rekey_1.resign_checked(&pub_designated_1, &msg, &sig_b_2),
+            Err(Error::InvalidSignature)
+        );
+
+        // Nor do unscoped re-signing keys work across epochs.
+        let rekey = priv_alice.resigning_key(&pub_bob);
+        assert_ne!(rekey.resign(&sig_b_1), priv_alice.sign(&msg));
+        assert_eq!(
+            priv_alice.resigning_key_for_epoch(&pub_bob, b"", kdf),
+            Ok(priv_alice.resigning_key_with_kdf(&pub_bob, kdf))
+        );
+
+        // Applications scope their own designated keys to epochs, apart from
+        // any other DST's.
+        let app_kdf = DesignatedKeyKdf::HkdfSha256(b"NUBLS_TEST_APP_");
+        let app_rekey = priv_alice
+            .resigning_key_for_epoch(&pub_bob, b"epoch-1", app_kdf)
+            .unwrap();
+        let app_sig_b = priv_bob
+            .designated_key_for_epoch(&pub_alice, b"epoch-1", app_kdf)
+            .unwrap()
+            .sign(&msg);
+        assert_eq!(app_rekey.resign(&app_sig_b), priv_alice.sign(&msg));
+        assert_ne!(app_rekey, rekey_1);
+        assert_ne!(rekey_1.resign(&app_sig_b), priv_alice.sign(&msg));
+
+        // The legacy derivation can't be scoped.
+        assert_eq!(
+            priv_alice.resigning_key_for_epoch(&pub_bob, b"epoch-1", DesignatedKeyKdf::Legacy),
+            Err(Error::UnsupportedEpoch)
+        );
+        assert_eq!(
+            priv_bob.designated_key_for_epoch(&pub_alice, b"epoch-1", DesignatedKeyKdf::Legacy),
+            Err(Error::UnsupportedEpoch)
+        );
+    }
+
+    #[test]
+    fn test_checked_re_signature() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+        let other_msg = G2Affine::from(G2Affine::generator() * rand.0.double());
+
+        let rekey_ab = priv_alice.resigning_key(&pub_bob);
+        let designated_bob = priv_bob.designated_key(&pub_alice);
+        let pub_designated_bob = designated_bob.public_key();
+        let sig_b = designated_bob.sign(&msg);
+
+        assert_eq!(
+            rekey_ab.resign_checked(&pub_designated_bob, &msg, &sig_b),
+            Ok(priv_alice.sign(&msg))
+        );
+
+        // Signatures on other messages, under Bob's own key, or that are
+        // garbage points are never re-signed.
+        assert_eq!(
+            rekey_ab.resign_checked(&pub_designated_bob, &other_msg, &sig_b),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            rekey_ab.resign_checked(&pub_designated_bob, &msg, &priv_bob.sign(&msg)),
+            Err(Error::InvalidSignature)
+        );
+        let garbage = Signature(G2Affine::generator(), None);
+        assert_eq!(
+            rekey_ab.resign_checked(&pub_designated_bob, &msg, &garbage),
+            Err(Error::InvalidSignature)
+        );
+
+        // Proxies holding fragments of the re-signing key check it too.
+        let rekey_frags = priv_alice.split_resigning_key(&pub_bob, 2, 3);
+        assert_eq!(
+            rekey_frags[0].resign_checked(&pub_designated_bob, &msg, &sig_b),
+            Ok(rekey_frags[0].resign(&sig_b))
+        );
+        assert_eq!(
+            rekey_frags[1].resign_checked(&pub_designated_bob, &other_msg, &sig_b),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_designated_public_key() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        let designated_bob = priv_bob.designated_key(&pub_alice);
+        let sig_b = designated_bob.sign(&msg);
+
+        // Bob and Alice both derive Bob's designated public key.
+        let pub_designated_bob = priv_bob.designated_public_key(&pub_alice);
+        assert_eq!(pub_designated_bob, designated_bob.public_key());
+        assert_eq!(
+            priv_alice.designated_public_key(&pub_bob),
+            pub_designated_bob
+        );
+        assert_eq!(
+            pub_designated_bob.verify(&msg, &sig_b),
+            VerificationResult::Valid
+        );
+
+        // A proxy derives it from Alice's public key and its re-signing key,
+        // and uses it to audit the signatures it re-signs.
+        let rekey_ab = priv_alice.resigning_key(&pub_bob);
+        assert_eq!(
+            pub_alice.designated_public_key(&rekey_ab),
+            Ok(pub_designated_bob)
+        );
+        assert_eq!(
+            rekey_ab.resign_checked(&pub_designated_bob, &msg, &sig_b),
+            Ok(priv_alice.sign(&msg))
+        );
+
+        // It's specific to Bob and Alice.
+        let priv_carol = PrivateKey::random();
+        assert_ne!(
+            priv_bob.designated_public_key(&priv_carol.public_key()),
+            pub_designated_bob
+        );
+        assert_ne!(
+            pub_bob.designated_public_key(&rekey_ab),
+            Ok(pub_designated_bob)
+        );
+
+        let rekey_frags = priv_alice.split_resigning_key(&pub_bob, 2, 3);
+        assert_eq!(
+            pub_alice.designated_public_key(&rekey_frags[0]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(
+            pub_alice.designated_public_key(&ResigningKey(Scalar::zero(), None)),
+            Err(Error::NonCanonicalScalar)
+        );
+    }
+
+    #[test]
+    fn test_threshold_proxy_re_signature() {
+        let priv_alice = PrivateKey::random();
+        let pub_alice = priv_alice.public_key();
+
+        let priv_bob = PrivateKey::random();
+        let pub_bob = priv_bob.public_key();
+
+        let rand = PrivateKey::random();
+        let msg = G2Affine::from(G2Affine::generator() * rand.0);
+
+        // Alice splits the re-signing key 3-of-5 across five proxies.
+        let rekey_ab = priv_alice.resigning_key(&pub_bob);
+        let rekey_frags = priv_alice.split_resigning_key(&pub_bob, 3, 5);
+        assert_eq!(rekey_frags.len(), 5);
+        assert!(rekey_frags.iter().all(|fragment| fragment.is_fragment()));
+
+        // Each proxy re-signs Bob's designated signature with its fragment,
+        // and each re-signature fragment can be checked on its own.
+        let sig_b = priv_bob.designated_key(&pub_alice).sign(&msg);
+        let resig_frags = rekey_frags
+            .iter()
+            .map(|fragment| fragment.resign(&sig_b))
+            .collect::<Vec<_>>();
+        for (fragment, resig) in rekey_frags.iter().zip(resig_frags.iter()) {
+            assert!(resig.is_fragment());
+            assert_eq!(
+                fragment.public_key().verify_fragment(&sig_b.0, resig),
+                VerificationResult::Valid
+            );
+        }
+
+        // Any three proxies re-sign to Alice's signature.
+        let sig_a = Signature::assemble(&resig_frags[1..4]).unwrap();
+        assert_eq!(sig_a, priv_alice.sign(&msg));
+        assert_eq!(pub_alice.verify(&msg, &sig_a), VerificationResult::Valid);
+        assert_eq!(
+            Signature::assemble(&[resig_frags[4], resig_frags[0], resig_frags[2]]),
+            Ok(sig_a)
+        );
+
+        // Two proxies learn nothing of the re-signing key, and can't re-sign.
+        assert_ne!(ResigningKey::recover(&rekey_frags[..2]), Ok(rekey_ab));
+        let partial = Signature::assemble(&resig_frags[..2]).unwrap();
+        assert_ne!(partial, sig_a);
+        assert_eq!(
+            pub_alice.verify(&msg, &partial),
+            VerificationResult::Invalid
+        );
+    }
 }
@@ -1,11 +1,38 @@
 extern crate bls12_381;
 extern crate getrandom;
 
+mod batch;
+mod bidirectional;
 mod bls;
+mod chain;
+mod ciphersuite;
+mod dkg;
+mod dleq;
+mod error;
+mod fields;
+mod hash_to_curve;
+mod kdf;
 mod keys;
+mod prs;
+mod refresh;
+mod reshare;
 mod traits;
 mod utils;
+mod vss;
 
-pub use bls::{Signature, VerificationResult};
+pub use batch::BatchVerifier;
+pub use bidirectional::BidirectionalResigningKey;
+pub use bls::{Signature, VerificationResult, MAX_ROBUST_ASSEMBLY_SUBSETS};
+pub use chain::ResigningChain;
+pub use ciphersuite::Ciphersuite;
+pub use dkg::{DkgMessage, DkgOutput, DkgParticipant};
+pub use dleq::ResigningKeyProof;
+pub use error::Error;
+pub use hash_to_curve::{hash_to_g1, hash_to_g2};
+pub use kdf::{DesignatedKeyKdf, DESIGNATED_KEY_DST};
 pub use keys::{PrivateKey, PublicKey};
+pub use prs::{DesignatedKey, ResigningKey};
+pub use refresh::refresh_dealing;
+pub use reshare::combine_reshares;
 pub use traits::{PRSKey, ThresholdKey, ThresholdSignature};
+pub use vss::{BlindingShare, FeldmanCommitments, PedersenCommitments};
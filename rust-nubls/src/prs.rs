@@ -0,0 +1,414 @@
+use bls12_381::{G2Affine, Scalar};
+
+use crate::bls::{Signature, VerificationResult};
+use crate::error::Error;
+use crate::keys::{PrivateKey, PublicKey, SCALAR_BYTES_LENGTH};
+use crate::traits::ThresholdKey;
+use crate::utils::{
+    fragment_id, fragment_index_from_bytes, fragment_index_length, fragment_index_to_bytes,
+    scalar_from_bytes,
+};
+
+/// The tag byte that a serialized `DesignatedKey` starts with.
+const DESIGNATED_KEY_TAG: u8 = 0x01;
+
+/// The tag byte that a serialized `ResigningKey` starts with.
+const RESIGNING_KEY_TAG: u8 = 0x02;
+
+/// The length of a serialized key's tag byte.
+const TAG_BYTES_LENGTH: usize = 1;
+
+/// A `DesignatedKey` is the key that Bob signs with so that his signatures can
+/// be re-signed to Alice's key, as returned by `PRSKey::designated_key`.
+///
+/// Signatures under it don't verify under Bob's own `PublicKey`, only under
+/// the designated key's `public_key`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DesignatedKey(pub(crate) Scalar);
+
+/// A `ResigningKey` is the key that a proxy uses to re-sign signatures under
+/// Bob's designated key to Alice's key, as returned by `PRSKey::resigning_key`.
+///
+/// It can't sign anything on its own. A `ResigningKey` can be split among
+/// several proxies with `ThresholdKey::split`, in which case each fragment
+/// re-signs to a signature fragment.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ResigningKey(pub(crate) Scalar, pub(crate) Option<Scalar>);
+
+impl DesignatedKey {
+    /// Returns the `PublicKey` of the designated key, which verifies the
+    /// signatures under it.
+    pub fn public_key(&self) -> PublicKey {
+        self.private_key().public_key()
+    }
+
+    /// Signs a `message_element` in G_2 with the designated key.
+    pub fn sign(&self, message_element: &G2Affine) -> Signature {
+        self.private_key().sign(message_element)
+    }
+
+    /// Signs the raw `message` bytes with the designated key, as in
+    /// `PrivateKey::sign_message`.
+    pub fn sign_message(&self, message: &[u8]) -> Signature {
+        self.private_key().sign_message(message)
+    }
+
+    /// Returns the length of the `DesignatedKey` once serialized with
+    /// `to_bytes`, which is always 33 bytes.
+    pub fn serialized_size(&self) -> usize {
+        TAG_BYTES_LENGTH + SCALAR_BYTES_LENGTH
+    }
+
+    /// Serializes the `DesignatedKey` by filling a buffer passed as an
+    /// argument, as a tag byte followed by the 32 byte key.
+    /// If the buffer is shorter than `serialized_size`, this returns
+    /// `Error::InvalidLength`.
+    pub fn to_bytes(&self, buff: &mut [u8]) -> Result<(), Error> {
+        if buff.len() < self.serialized_size() {
+            return Err(Error::InvalidLength(buff.len()));
+        }
+        buff[0] = DESIGNATED_KEY_TAG;
+        buff[TAG_BYTES_LENGTH..self.serialized_size()].copy_from_slice(&self.0.to_bytes()[..]);
+        Ok(())
+    }
+
+    /// Deserializes from a `&[u8]` to a `DesignatedKey`.
+    /// This returns an `Error` if the input is not 33 bytes long, is not a
+    /// serialized `DesignatedKey`, or is not canonical.
+    ///
+    /// Unlike a `ResigningKey`, a `DesignatedKey` is never a fragment, so
+    /// there is no 37 or 65 byte form with a fragment index.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DesignatedKey, Error> {
+        if bytes.len() != TAG_BYTES_LENGTH + SCALAR_BYTES_LENGTH {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        check_tag(bytes[0], DESIGNATED_KEY_TAG)?;
+        Ok(DesignatedKey(scalar_from_bytes(
+            &bytes[TAG_BYTES_LENGTH..],
+        )?))
+    }
+
+    fn private_key(&self) -> PrivateKey {
+        PrivateKey(self.0, None)
+    }
+}
+
+impl ResigningKey {
+    /// Re-Signs a `Signature` from $\sigma_{\phi_B}$ to $\sigma_A$.
+    /// This is done by multiplying the `Signature` by the re-signing key:
+    /// $\sigma_A = \phi_{B \rightarrow A} \cdot \sigma_{\phi_B}$
+    ///
+    /// This re-signs any `signature` without checking it, so prefer
+    /// `resign_checked` unless the signature is already verified.
+    pub fn resign(&self, signature: &Signature) -> Signature {
+        Signature::new(&self.private_key(), &signature.0)
+    }
+
+    /// Verifies a `signature` on the `message_element` under Bob's
+    /// `designated_pubkey` before re-signing it to Alice's key.
+    ///
+    /// Returns `Error::InvalidSignature` if the signature doesn't verify, so
+    /// that an invalid signature is never re-signed.
+    pub fn resign_checked(
+        &self,
+        designated_pubkey: &PublicKey,
+        message_element: &G2Affine,
+        signature: &Signature,
+    ) -> Result<Signature, Error> {
+        match designated_pubkey.verify(message_element, signature) {
+            VerificationResult::Valid => Ok(self.resign(signature)),
+            VerificationResult::Invalid => Err(Error::InvalidSignature),
+        }
+    }
+
+    /// Returns the `PublicKey` of the re-signing key. For a fragment, this
+    /// verifies its re-signature fragments with `PublicKey::verify_fragment`,
+    /// using the signature under Bob's designated key as the message element.
+    pub fn public_key(&self) -> PublicKey {
+        self.private_key().public_key()
+    }
+
+    /// Returns the fragment ID of a fragment of a re-signing key, as in
+    /// `PrivateKey::fragment_id`.
+    pub fn fragment_id(&self) -> Option<u32> {
+        self.1.as_ref().and_then(fragment_id)
+    }
+
+    /// Returns the length of the `ResigningKey` once serialized with `to_bytes`.
+    pub fn serialized_size(&self) -> usize {
+        TAG_BYTES_LENGTH + SCALAR_BYTES_LENGTH + self.1.as_ref().map_or(0, fragment_index_length)
+    }
+
+    /// Serializes the `ResigningKey` by filling a buffer passed as an
+    /// argument, as a tag byte followed by the 32 byte key, and the fragment
+    /// index of a fragment (see `from_bytes`).
+    /// If the buffer is shorter than `serialized_size`, this returns
+    /// `Error::InvalidLength`.
+    pub fn to_bytes(&self, buff: &mut [u8]) -> Result<(), Error> {
+        let length = self.serialized_size();
+        if buff.len() < length {
+            return Err(Error::InvalidLength(buff.len()));
+        }
+        let key_end = TAG_BYTES_LENGTH + SCALAR_BYTES_LENGTH;
+        buff[0] = RESIGNING_KEY_TAG;
+        buff[TAG_BYTES_LENGTH..key_end].copy_from_slice(&self.0.to_bytes()[..]);
+        if let Some(fragment_index) = self.1 {
+            fragment_index_to_bytes(&fragment_index, &mut buff[key_end..length]);
+        }
+        Ok(())
+    }
+
+    /// Deserializes from a `&[u8]` to a `ResigningKey`.
+    /// This returns an `Error` if the input is not 33, 37 or 65 bytes long,
+    /// is not a serialized `ResigningKey`, or is not a canonical, non-zero key.
+    ///
+    /// A `ResigningKey` can be serialized in three ways, as a tag byte and
+    /// the 32 byte key followed by:
+    ///  1. Nothing, for 33 bytes -- This is the case when it's not a fragment.
+    ///
+    ///  2. A 4 byte big-endian fragment ID, for 37 bytes -- This is the case
+    ///     for fragments split by this version of NuBLS.
+    ///
+    ///  3. A 32 byte fragment index, for 65 bytes -- This is the case for
+    ///     fragments with a random 32 byte index, as split by older versions
+    ///     of NuBLS.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ResigningKey, Error> {
+        let key_end = TAG_BYTES_LENGTH + SCALAR_BYTES_LENGTH;
+        let fragment_index = match bytes.len() {
+            33 => None,
+            37 | 65 => Some(fragment_index_from_bytes(&bytes[key_end..])?),
+            length => return Err(Error::InvalidLength(length)),
+        };
+        check_tag(bytes[0], RESIGNING_KEY_TAG)?;
+        // A zero key maps every signature to the identity.
+        let key = scalar_from_bytes(&bytes[TAG_BYTES_LENGTH..key_end])?;
+        if key == Scalar::zero() {
+            return Err(Error::NonCanonicalScalar);
+        }
+        Ok(ResigningKey(key, fragment_index))
+    }
+
+    pub(crate) fn private_key(&self) -> PrivateKey {
+        PrivateKey(self.0, self.1)
+    }
+}
+
+impl ThresholdKey for ResigningKey {
+    /// Splits the re-signing key into `n` fragments for `n` proxies with a
+    /// threshold of `m`, as in `PrivateKey::split`.
+    fn split(&self, m: usize, n: usize) -> Vec<ResigningKey> {
+        self.private_key()
+            .split(m, n)
+            .into_iter()
+            .map(|fragment| ResigningKey(fragment.0, fragment.1))
+            .collect()
+    }
+
+    /// Recovers a `ResigningKey` from a threshold of its `fragments`, as in
+    /// `PrivateKey::recover`.
+    fn recover(fragments: &[ResigningKey]) -> Result<ResigningKey, Error> {
+        let fragments = fragments
+            .iter()
+            .map(ResigningKey::private_key)
+            .collect::<Vec<_>>();
+        let key = PrivateKey::recover(&fragments[..])?;
+        Ok(ResigningKey(key.0, None))
+    }
+
+    /// Returns whether or not this is a fragment of a re-signing key.
+    fn is_fragment(&self) -> bool {
+        self.1.is_some()
+    }
+}
+
+/// Checks that a serialized key starts with the `expected` tag byte.
+fn check_tag(tag: u8, expected: u8) -> Result<(), Error> {
+    if tag != expected {
+        return Err(Error::InvalidKeyType(tag));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kdf::DesignatedKeyKdf;
+    use crate::traits::PRSKey;
+    use crate::utils::from_hex;
+    use std::collections::HashMap;
+
+    /// Parses the vendored Penumbral test vectors, which pynubls also runs,
+    /// into a map of each vector's values by name.
+    fn penumbral_vectors() -> Vec<HashMap<&'static str, &'static str>> {
+        include_str!("../../test-vectors/penumbral.txt")
+            .split("\n\n")
+            .map(|block| {
+                block
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .map(|line| {
+                        let (name, value) = line.split_once('=').unwrap();
+                        (name.trim(), value.trim())
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .filter(|vector| !vector.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_penumbral_vectors() {
+        let vectors = penumbral_vectors();
+        assert_eq!(vectors.len(), 4);
+
+        for vector in vectors {
+            let scalar = |name| scalar_from_bytes(&from_hex(vector[name])).unwrap();
+            let public_key = |name| PublicKey::from_bytes(&from_hex(vector[name])).unwrap();
+            let signature = |name| Signature::from_bytes(&from_hex(vector[name])).unwrap();
+
+            let priv_alice = PrivateKey(scalar("alice_sk"), None);
+            let priv_bob = PrivateKey(scalar("bob_sk"), None);
+            let pub_alice = priv_alice.public_key();
+            let pub_bob = priv_bob.public_key();
+            assert_eq!(pub_alice, public_key("alice_pk"));
+            assert_eq!(pub_bob, public_key("bob_pk"));
+
+            let dst = from_hex(vector["dst"]);
+            let epoch = from_hex(vector["epoch"]);
+            let kdf = match vector["kdf"] {
+                "hkdf-sha256" => DesignatedKeyKdf::HkdfSha256(&dst),
+                "legacy" => DesignatedKeyKdf::Legacy,
+                kdf => panic!("Unknown KDF in test vector: {}", kdf),
+            };
+            let (designated_key, resigning_key) = if epoch.is_empty() {
+                (
+                    priv_bob.designated_key_with_kdf(&pub_alice, kdf),
+                    priv_alice.resigning_key_with_kdf(&pub_bob, kdf),
+                )
+            } else {
+                (
+                    priv_bob
+                        .designated_key_for_epoch(&pub_alice, &epoch, kdf)
+                        .unwrap(),
+                    priv_alice
+                        .resigning_key_for_epoch(&pub_bob, &epoch, kdf)
+                        .unwrap(),
+                )
+            };
+            assert_eq!(designated_key, DesignatedKey(scalar("designated_key")));
+            assert_eq!(resigning_key, ResigningKey(scalar("resigning_key"), None));
+
+            let pub_designated_bob = public_key("designated_pk");
+            assert_eq!(designated_key.public_key(), pub_designated_bob);
+            assert_eq!(
+                pub_alice.designated_public_key(&resigning_key),
+                Ok(pub_designated_bob)
+            );
+
+            let message = from_hex(vector["message"]);
+            let designated_sig = designated_key.sign_message(&message);
+            assert_eq!(designated_sig, signature("designated_signature"));
+            assert_eq!(
+                pub_designated_bob.verify_message(&message, &designated_sig),
+                VerificationResult::Valid
+            );
+
+            let resigned_sig = resigning_key.resign(&designated_sig);
+            assert_eq!(resigned_sig, signature("resigned_signature"));
+            assert_eq!(resigned_sig, priv_alice.sign_message(&message));
+            assert_eq!(
+                pub_alice.verify_message(&message, &resigned_sig),
+                VerificationResult::Valid
+            );
+        }
+    }
+
+    #[test]
+    fn test_designated_key_serialization() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let designated_key = priv_bob.designated_key(&priv_alice.public_key());
+
+        let mut buff = [0u8; 33];
+        assert_eq!(designated_key.serialized_size(), 33);
+        designated_key.to_bytes(&mut buff).unwrap();
+        assert_eq!(buff[0], DESIGNATED_KEY_TAG);
+        assert_eq!(DesignatedKey::from_bytes(&buff), Ok(designated_key));
+
+        assert_eq!(
+            designated_key.to_bytes(&mut [0u8; 32]),
+            Err(Error::InvalidLength(32))
+        );
+        assert_eq!(
+            DesignatedKey::from_bytes(&buff[1..]),
+            Err(Error::InvalidLength(32))
+        );
+    }
+
+    #[test]
+    fn test_resigning_key_serialization() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let resigning_key = priv_alice.resigning_key(&priv_bob.public_key());
+
+        let mut buff = [0u8; 33];
+        assert_eq!(resigning_key.serialized_size(), 33);
+        resigning_key.to_bytes(&mut buff).unwrap();
+        assert_eq!(buff[0], RESIGNING_KEY_TAG);
+        assert_eq!(ResigningKey::from_bytes(&buff), Ok(resigning_key));
+
+        let fragment = resigning_key.split(2, 3)[2];
+        let mut frag_buff = [0u8; 37];
+        assert_eq!(fragment.serialized_size(), 37);
+        fragment.to_bytes(&mut frag_buff).unwrap();
+        assert_eq!(ResigningKey::from_bytes(&frag_buff), Ok(fragment));
+        assert_eq!(fragment.fragment_id(), Some(3));
+
+        assert_eq!(
+            fragment.to_bytes(&mut [0u8; 33]),
+            Err(Error::InvalidLength(33))
+        );
+        assert_eq!(
+            ResigningKey::from_bytes(&buff[1..]),
+            Err(Error::InvalidLength(32))
+        );
+        let legacy_fragment = ResigningKey(fragment.0, Some(PrivateKey::random().0));
+        let mut legacy_buff = [0u8; 65];
+        assert_eq!(legacy_fragment.serialized_size(), 65);
+        legacy_fragment.to_bytes(&mut legacy_buff).unwrap();
+        assert_eq!(ResigningKey::from_bytes(&legacy_buff), Ok(legacy_fragment));
+
+        let mut zero = [0u8; 33];
+        zero[0] = RESIGNING_KEY_TAG;
+        assert_eq!(
+            ResigningKey::from_bytes(&zero),
+            Err(Error::NonCanonicalScalar)
+        );
+    }
+
+    #[test]
+    fn test_keys_dont_deserialize_as_each_other() {
+        let priv_alice = PrivateKey::random();
+        let priv_bob = PrivateKey::random();
+        let resigning_key = priv_alice.resigning_key(&priv_bob.public_key());
+        let designated_key = priv_bob.designated_key(&priv_alice.public_key());
+
+        let mut resigning_buff = [0u8; 33];
+        resigning_key.to_bytes(&mut resigning_buff).unwrap();
+        let mut designated_buff = [0u8; 33];
+        designated_key.to_bytes(&mut designated_buff).unwrap();
+
+        assert_eq!(
+            DesignatedKey::from_bytes(&resigning_buff),
+            Err(Error::InvalidKeyType(RESIGNING_KEY_TAG))
+        );
+        assert_eq!(
+            ResigningKey::from_bytes(&designated_buff),
+            Err(Error::InvalidKeyType(DESIGNATED_KEY_TAG))
+        );
+        assert_eq!(
+            PrivateKey::from_bytes(&resigning_buff),
+            Err(Error::InvalidLength(33))
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use bls12_381::{G1Projective, Scalar};
+
+use crate::bls::VerificationResult;
+use crate::error::Error;
+use crate::keys::{check_ids, fragments_from_poly, PrivateKey, PublicKey};
+use crate::utils::random_poly;
+use crate::vss::FeldmanCommitments;
+
+/// Deals one fragment holder's contribution to a refresh round of a threshold
+/// key with a threshold of `m`, held by the fragment holders with `ids`.
+///
+/// Proactive secret sharing periodically refreshes the fragments of a key, so
+/// that fragments leaked before a refresh are useless when combined with
+/// fragments from after it. In each round, every fragment holder deals a
+/// sharing of zero with this, sending each holder their share privately and
+/// publishing the `FeldmanCommitments`. Each holder then applies every
+/// dealing to their fragment with `PrivateKey::refresh`, which changes the
+/// fragments but not the group `PublicKey` they recover to.
+///
+/// Returns `Error::InvalidFragmentIndex` or `Error::DuplicateFragmentIndex`
+/// if an ID is zero or repeated.
+pub fn refresh_dealing(
+    m: usize,
+    ids: &[u32],
+) -> Result<(Vec<PrivateKey>, FeldmanCommitments), Error> {
+    check_ids(ids)?;
+    let coeffs = random_poly(&Scalar::zero(), m);
+    Ok((
+        fragments_from_poly(&coeffs[..], ids),
+        FeldmanCommitments::new(&coeffs[..]),
+    ))
+}
+
+impl PrivateKey {
+    /// Refreshes a fragment of a key with a threshold of `m` by adding the
+    /// `dealings` of a refresh round to it, and returns the new fragment.
+    ///
+    /// Each of the `dealings` is this fragment's share from one holder's
+    /// `refresh_dealing`, along with that holder's commitments. Every holder
+    /// must apply the same dealings, e.g. those from every holder that
+    /// published commitments in the round, or their fragments will no longer
+    /// combine.
+    ///
+    /// Returns `Error::InvalidRefreshShare` with the position of the first
+    /// dealing that isn't a valid sharing of zero with a threshold of `m`,
+    /// or whose share isn't valid for this fragment. Such dealings should be
+    /// reported to the other holders, and excluded by all of them.
+    pub fn refresh(
+        &self,
+        m: usize,
+        dealings: &[(PrivateKey, FeldmanCommitments)],
+    ) -> Result<PrivateKey, Error> {
+        let fragment_index = self.1.ok_or(Error::InvalidFragmentIndex)?;
+        let mut result = self.0;
+        for (position, (share, commitments)) in dealings.iter().enumerate() {
+            if !is_zero_sharing(commitments, m)
+                || share.1 != Some(fragment_index)
+                || commitments.verify(&share.0, &fragment_index) == VerificationResult::Invalid
+            {
+                return Err(Error::InvalidRefreshShare(position));
+            }
+            result += share.0;
+        }
+        Ok(PrivateKey(result, self.1))
+    }
+}
+
+impl PublicKey {
+    /// Refreshes the `PublicKey` of a fragment of a key with a threshold of
+    /// `m`, given the commitments of every dealing applied to the fragment
+    /// with `PrivateKey::refresh`.
+    ///
+    /// The result is the `PublicKey` of the refreshed fragment, which
+    /// verifies its signature fragments with `PublicKey::verify_fragment`.
+    /// Returns `Error::InvalidRefreshShare` with the position of the first
+    /// `commitments` that aren't to a sharing of zero with a threshold of `m`.
+    pub fn refresh(
+        &self,
+        m: usize,
+        commitments: &[FeldmanCommitments],
+    ) -> Result<PublicKey, Error> {
+        let fragment_index = self.1.ok_or(Error::InvalidFragmentIndex)?;
+        let mut result = G1Projective::from(self.0);
+        for (position, commitments) in commitments.iter().enumerate() {
+            if !is_zero_sharing(commitments, m) {
+                return Err(Error::InvalidRefreshShare(position));
+            }
+            result += commitments.evaluate(&fragment_index);
+        }
+        Ok(PublicKey(result.into(), self.1))
+    }
+}
+
+/// Checks that `commitments` are to a polynomial with a threshold of `m`
+/// whose constant term is zero, so that adding it leaves the secret as is.
+fn is_zero_sharing(commitments: &FeldmanCommitments, m: usize) -> bool {
+    m > 0 && commitments.threshold() == m && bool::from(commitments.0[0].is_identity())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::Signature;
+    use crate::traits::{ThresholdKey, ThresholdSignature};
+
+    #[test]
+    fn test_refresh() {
+        let priv_a = PrivateKey::random();
+        let ids = [1, 2, 3, 4, 5];
+        let (old_frags, commitments) = priv_a.split_verifiable(3, 5);
+
+        // Every holder deals a sharing of zero, and applies every dealing.
+        let dealings = ids
+            .iter()
+            .map(|_| refresh_dealing(3, &ids).unwrap())
+            .collect::<Vec<_>>();
+        let new_frags = old_frags
+            .iter()
+            .enumerate()
+            .map(|(i, fragment)| {
+                let shares = dealings
+                    .iter()
+                    .map(|(shares, commitments)| (shares[i], commitments.clone()))
+                    .collect::<Vec<_>>();
+                fragment.refresh(3, &shares[..]).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        for (old_frag, new_frag) in old_frags.iter().zip(new_frags.iter()) {
+            assert_ne!(old_frag, new_frag);
+            assert_eq!(old_frag.fragment_id(), new_frag.fragment_id());
+        }
+        assert_eq!(PrivateKey::recover(&new_frags[2..5]), Ok(priv_a));
+
+        // Old fragments don't combine with new ones.
+        let mixed = [old_frags[0], old_frags[1], new_frags[2]];
+        assert_ne!(PrivateKey::recover(&mixed), Ok(priv_a));
+
+        // The fragments' public keys can be refreshed from the commitments.
+        let refresh_commitments = dealings
+            .iter()
+            .map(|(_, commitments)| commitments.clone())
+            .collect::<Vec<_>>();
+        for new_frag in new_frags.iter() {
+            let old_key = commitments.fragment_public_key(&new_frag.1.unwrap());
+            assert_eq!(
+                old_key.refresh(3, &refresh_commitments[..]),
+                Ok(new_frag.public_key())
+            );
+        }
+
+        let sigs = new_frags[..3]
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Signature::assemble(&sigs[..]),
+            Ok(priv_a.sign_message(b"NuBLS!"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_refresh() {
+        let priv_a = PrivateKey::random();
+        let ids = [1, 2, 3];
+        let fragment = priv_a.split(2, 3)[0];
+        let (shares, commitments) = refresh_dealing(2, &ids).unwrap();
+        let honest = (shares[0], commitments.clone());
+
+        // A dealing of a non-zero secret would change the group key.
+        let (bad_shares, bad_commitments) = priv_a.split_verifiable(2, 3);
+        assert_eq!(
+            fragment.refresh(
+                2,
+                &[honest.clone(), (bad_shares[0], bad_commitments.clone())]
+            ),
+            Err(Error::InvalidRefreshShare(1))
+        );
+        assert_eq!(
+            fragment
+                .public_key()
+                .refresh(2, &[commitments.clone(), bad_commitments]),
+            Err(Error::InvalidRefreshShare(1))
+        );
+
+        // So would a dealing with a higher threshold.
+        let (high_shares, high_commitments) = refresh_dealing(3, &ids).unwrap();
+        assert_eq!(
+            fragment.refresh(2, &[(high_shares[0], high_commitments)]),
+            Err(Error::InvalidRefreshShare(0))
+        );
+
+        // Shares must be valid, and for this fragment.
+        let tampered = PrivateKey(shares[0].0 + Scalar::one(), shares[0].1);
+        assert_eq!(
+            fragment.refresh(2, &[(tampered, commitments.clone())]),
+            Err(Error::InvalidRefreshShare(0))
+        );
+        assert_eq!(
+            fragment.refresh(2, &[(shares[1], commitments.clone())]),
+            Err(Error::InvalidRefreshShare(0))
+        );
+
+        assert_eq!(
+            priv_a.refresh(2, &[honest]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(
+            refresh_dealing(2, &[1, 1]).unwrap_err(),
+            Error::DuplicateFragmentIndex
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use bls12_381::{G1Affine, G1Projective, Scalar};
+
+use crate::bls::VerificationResult;
+use crate::error::Error;
+use crate::keys::{check_ids, fragments_from_poly, PrivateKey, PublicKey};
+use crate::utils::{fragment_indices, lambda_coeff, random_poly};
+use crate::vss::FeldmanCommitments;
+
+impl PrivateKey {
+    /// Reshares a fragment to a new committee of fragment holders with `ids`,
+    /// with a new threshold of `m`.
+    ///
+    /// Resharing moves a threshold key to a new threshold and set of holders
+    /// without ever recovering the key. A threshold of the old holders each
+    /// reshare their fragment with this, sending each new holder their
+    /// sub-share privately and publishing the `FeldmanCommitments`. Each new
+    /// holder then combines the sub-shares it received into its new fragment
+    /// with `combine_reshares`.
+    ///
+    /// Returns `Error::InvalidFragmentIndex` if this is not a fragment, or if
+    /// an ID is zero, and `Error::DuplicateFragmentIndex` if one is repeated.
+    pub fn reshare(
+        &self,
+        m: usize,
+        ids: &[u32],
+    ) -> Result<(Vec<PrivateKey>, FeldmanCommitments), Error> {
+        if self.1.is_none() {
+            return Err(Error::InvalidFragmentIndex);
+        }
+        check_ids(ids)?;
+        let coeffs = random_poly(&self.0, m);
+        Ok((
+            fragments_from_poly(&coeffs[..], ids),
+            FeldmanCommitments::new(&coeffs[..]),
+        ))
+    }
+}
+
+/// Combines the sub-shares a new fragment holder received during resharing
+/// (see `PrivateKey::reshare`) into its fragment of the key with the group
+/// `public_key`, with a new threshold of `m`.
+///
+/// Each of the `dealings` holds the `PublicKey` of the old fragment that was
+/// reshared, e.g. from `FeldmanCommitments::fragment_public_key`, along with
+/// the sub-share for this holder and the dealer's commitments. Every new
+/// holder must combine dealings from the same old holders.
+///
+/// Alongside the new fragment, this returns the `FeldmanCommitments` of the
+/// new sharing, which verify the new fragments and give their public keys.
+///
+/// Returns `Error::InvalidReshare` with the position of the first dealing
+/// whose commitments don't have a threshold of `m` or don't match its old
+/// fragment, or whose sub-share isn't valid for this holder, and
+/// `Error::NotEnoughFragments` if the dealings don't reshare the key with the
+/// group `public_key`, e.g. because there are fewer than its threshold.
+pub fn combine_reshares(
+    m: usize,
+    public_key: &PublicKey,
+    dealings: &[(PublicKey, PrivateKey, FeldmanCommitments)],
+) -> Result<(PrivateKey, FeldmanCommitments), Error> {
+    let dealer_indices = fragment_indices(dealings.iter().map(|dealing| dealing.0 .1))?;
+    let new_index = dealings[0].1 .1.ok_or(Error::InvalidReshare(0))?;
+    for (position, (dealer, sub_share, commitments)) in dealings.iter().enumerate() {
+        if m == 0
+            || commitments.threshold() != m
+            || commitments.0[0] != dealer.0
+            || sub_share.1 != Some(new_index)
+            || commitments.verify(&sub_share.0, &new_index) == VerificationResult::Invalid
+        {
+            return Err(Error::InvalidReshare(position));
+        }
+    }
+
+    // The new sharing polynomial is the Lagrange combination of the dealers'
+    // polynomials, and so are its fragments and commitments.
+    let lambdas = dealer_indices
+        .iter()
+        .map(|index| lambda_coeff(index, &dealer_indices[..]))
+        .collect::<Vec<_>>();
+    let fragment = dealings
+        .iter()
+        .zip(lambdas.iter())
+        .fold(Scalar::zero(), |acc, ((_, sub_share, _), lambda)| {
+            acc + sub_share.0 * lambda
+        });
+    let commitments = (0..m)
+        .map(|k| {
+            let coeff = dealings.iter().zip(lambdas.iter()).fold(
+                G1Projective::identity(),
+                |acc, ((_, _, commitments), lambda)| acc + commitments.0[k] * lambda,
+            );
+            G1Affine::from(coeff)
+        })
+        .collect::<Vec<_>>();
+    let commitments = FeldmanCommitments(commitments);
+    if commitments.public_key().0 != public_key.0 {
+        return Err(Error::NotEnoughFragments);
+    }
+    Ok((PrivateKey(fragment, Some(new_index)), commitments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::Signature;
+    use crate::traits::{ThresholdKey, ThresholdSignature};
+
+    /// Reshares the old fragments at `dealers` to a `m`-of-`ids` committee,
+    /// and returns every new holder's dealings.
+    fn reshare(
+        old_frags: &[PrivateKey],
+        old_commitments: &FeldmanCommitments,
+        dealers: &[usize],
+        m: usize,
+        ids: &[u32],
+    ) -> Vec<Vec<(PublicKey, PrivateKey, FeldmanCommitments)>> {
+        let reshares = dealers
+            .iter()
+            .map(|&dealer| old_frags[dealer].reshare(m, ids).unwrap())
+            .collect::<Vec<_>>();
+        (0..ids.len())
+            .map(|j| {
+                dealers
+                    .iter()
+                    .zip(reshares.iter())
+                    .map(|(&dealer, (sub_shares, commitments))| {
+                        let dealer_key =
+                            old_commitments.fragment_public_key(&old_frags[dealer].1.unwrap());
+                        (dealer_key, sub_shares[j], commitments.clone())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reshare_to_new_committee() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+        let (old_frags, old_commitments) = priv_a.split_verifiable(3, 5);
+
+        // Three of the five old holders reshare to a 4-of-7 committee.
+        let ids = [10, 11, 12, 13, 14, 15, 16];
+        let dealings = reshare(&old_frags, &old_commitments, &[1, 3, 4], 4, &ids);
+        let results = dealings
+            .iter()
+            .map(|dealings| combine_reshares(4, &pub_a, &dealings[..]).unwrap())
+            .collect::<Vec<_>>();
+
+        let new_frags = results
+            .iter()
+            .map(|(fragment, _)| *fragment)
+            .collect::<Vec<_>>();
+        let new_commitments = &results[0].1;
+        for ((fragment, commitments), id) in results.iter().zip(ids.iter()) {
+            assert_eq!(fragment.fragment_id(), Some(*id));
+            assert_eq!(commitments, new_commitments);
+            assert_eq!(
+                fragment.verify_fragment(new_commitments),
+                VerificationResult::Valid
+            );
+            assert_eq!(
+                new_commitments.fragment_public_key(&fragment.1.unwrap()),
+                fragment.public_key()
+            );
+        }
+        assert_eq!(new_commitments.threshold(), 4);
+        assert_eq!(new_commitments.public_key(), pub_a);
+
+        assert_eq!(PrivateKey::recover(&new_frags[3..7]), Ok(priv_a));
+        assert_ne!(PrivateKey::recover(&new_frags[..3]), Ok(priv_a));
+
+        let sigs = new_frags[2..6]
+            .iter()
+            .map(|fragment| fragment.sign_message(b"NuBLS!"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Signature::assemble(&sigs[..]),
+            Ok(priv_a.sign_message(b"NuBLS!"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_reshares() {
+        let priv_a = PrivateKey::random();
+        let pub_a = priv_a.public_key();
+        let (old_frags, old_commitments) = priv_a.split_verifiable(3, 5);
+        let ids = [1, 2, 3];
+        let dealings = reshare(&old_frags, &old_commitments, &[0, 1, 2], 2, &ids);
+        let mut dealings = dealings[0].clone();
+
+        // Too few old holders can't reshare the key.
+        assert_eq!(
+            combine_reshares(2, &pub_a, &dealings[..2]),
+            Err(Error::NotEnoughFragments)
+        );
+
+        // Sub-shares must be valid and for the same holder.
+        let (dealer, sub_share, commitments) = dealings[1].clone();
+        let tampered = PrivateKey(sub_share.0 + Scalar::one(), sub_share.1);
+        dealings[1] = (dealer, tampered, commitments.clone());
+        assert_eq!(
+            combine_reshares(2, &pub_a, &dealings[..]),
+            Err(Error::InvalidReshare(1))
+        );
+
+        // A dealer can't reshare anything but its old fragment.
+        let (other_shares, other_commitments) = PrivateKey(old_frags[3].0, old_frags[1].1)
+            .reshare(2, &ids)
+            .unwrap();
+        dealings[1] = (dealer, other_shares[0], other_commitments);
+        assert_eq!(
+            combine_reshares(2, &pub_a, &dealings[..]),
+            Err(Error::InvalidReshare(1))
+        );
+
+        // Nor change the new threshold.
+        let (high_shares, high_commitments) = old_frags[1].reshare(3, &ids).unwrap();
+        dealings[1] = (dealer, high_shares[0], high_commitments);
+        assert_eq!(
+            combine_reshares(2, &pub_a, &dealings[..]),
+            Err(Error::InvalidReshare(1))
+        );
+
+        dealings[1] = dealings[0].clone();
+        assert_eq!(
+            combine_reshares(2, &pub_a, &dealings[..]),
+            Err(Error::DuplicateFragmentIndex)
+        );
+        assert_eq!(
+            combine_reshares(2, &pub_a, &[]),
+            Err(Error::NotEnoughFragments)
+        );
+        assert_eq!(
+            priv_a.reshare(2, &ids).unwrap_err(),
+            Error::InvalidFragmentIndex
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use crate::bls::Signature;
+use crate::error::Error;
+use crate::kdf::{DesignatedKeyKdf, BIDIRECTIONAL_KEY_DST};
 use crate::keys::PublicKey;
+use crate::prs::{DesignatedKey, ResigningKey};
 
 /// A trait that describes a key that can be used for threshold cryptography
 /// protocols. The key that has this trait implemented on it can be split into
@@ -16,7 +18,10 @@ pub trait ThresholdKey: Sized {
 
     /// The `recover` function returns the re-assembled key given the threshold
     /// `m` fragments.
-    fn recover(fragments: &[Self]) -> Self;
+    ///
+    /// Returns an `Error` if the fragments are empty, or if their fragment
+    /// indices are missing, zero, or duplicated.
+    fn recover(fragments: &[Self]) -> Result<Self, Error>;
 
     /// The `is_fragment` method returns a `bool` when the `PrivateKey` is
     /// used for a threshold computation.
@@ -31,7 +36,10 @@ pub trait ThresholdSignature: Sized {
     /// threshold amount of signatures.
     /// The fully-assembled signature can be verified by its corresponding
     /// threshold key.
-    fn assemble(fragments: &[Self]) -> Self;
+    ///
+    /// Returns an `Error` if the fragments are empty, or if their fragment
+    /// indices are missing, zero, or duplicated.
+    fn assemble(fragments: &[Self]) -> Result<Self, Error>;
 
     /// The `is_fragment` method returns a `bool` when the `PrivateKey` is
     /// used for a threshold computation.
@@ -43,14 +51,139 @@ pub trait ThresholdSignature: Sized {
 /// key to Alice's key.
 pub trait PRSKey: Sized {
     /// Generates a re-signing key from Bob's designated key to the `self` key.
-    fn resigning_key(&self, bob_pubkey: &PublicKey) -> Self;
+    ///
+    /// This derives Bob's designated key with the default `DesignatedKeyKdf`,
+    /// `Legacy`, as earlier versions of NuBLS did.
+    fn resigning_key(&self, bob_pubkey: &PublicKey) -> ResigningKey {
+        self.resigning_key_with_kdf(bob_pubkey, DesignatedKeyKdf::default())
+    }
+
+    /// Generates a re-signing key from Bob's designated key, derived with the
+    /// `kdf`, to the `self` key.
+    fn resigning_key_with_kdf(&self, bob_pubkey: &PublicKey, kdf: DesignatedKeyKdf)
+        -> ResigningKey;
+
+    /// Generates a re-signing key to the `self` key that only re-signs
+    /// signatures under Bob's designated key for the `epoch`, which may be any
+    /// label, e.g. an epoch number or a policy identifier.
+    ///
+    /// Alice revokes the proxies of an epoch by moving Bob to a new epoch and
+    /// issuing re-signing keys for it, without changing her own key.
+    ///
+    /// Bob's designated key is derived with the `kdf`, which must be
+    /// `DesignatedKeyKdf::HkdfSha256`: returns `Error::UnsupportedEpoch` for
+    /// `Legacy`, which can't be scoped.
+    fn resigning_key_for_epoch(
+        &self,
+        bob_pubkey: &PublicKey,
+        epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<ResigningKey, Error>;
+
+    /// Generates a re-signing key for an intermediate hop of a chain of
+    /// delegations (see `ResigningChain`), from the designated key for `self`
+    /// of the party with `from_pubkey`, to `self`'s designated key for the
+    /// party with `to_pubkey`.
+    ///
+    /// E.g. when Alice delegates to Bob, who delegates on to Carol, Bob's hop
+    /// re-signs from Carol's designated key for Bob to Bob's designated key
+    /// for Alice, which Alice's re-signing key then re-signs to her key.
+    ///
+    /// This derives both designated keys with the default `DesignatedKeyKdf`,
+    /// `Legacy`.
+    fn hop_resigning_key(&self, from_pubkey: &PublicKey, to_pubkey: &PublicKey) -> ResigningKey {
+        self.hop_resigning_key_with_kdf(from_pubkey, to_pubkey, DesignatedKeyKdf::default())
+    }
+
+    /// Generates a re-signing key for an intermediate hop of a chain of
+    /// delegations, as in `hop_resigning_key`, with both designated keys
+    /// derived with the `kdf`.
+    fn hop_resigning_key_with_kdf(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        kdf: DesignatedKeyKdf,
+    ) -> ResigningKey;
+
+    /// Generates a re-signing key for an intermediate hop of a chain of
+    /// delegations, as in `hop_resigning_key`, from the designated key for
+    /// `self` in the `from_epoch` to `self`'s designated key in the `to_epoch`.
+    ///
+    /// Each party of the chain revokes its own hop independently: `self`
+    /// revokes the party with `from_pubkey` by moving it to a new `from_epoch`,
+    /// and the party with `to_pubkey` revokes `self` with a new `to_epoch`.
+    ///
+    /// Both designated keys are derived with the `kdf`, as in
+    /// `resigning_key_for_epoch`.
+    fn hop_resigning_key_for_epoch(
+        &self,
+        from_pubkey: &PublicKey,
+        to_pubkey: &PublicKey,
+        from_epoch: &[u8],
+        to_epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<ResigningKey, Error>;
+
+    /// Splits the re-signing key from Bob's designated key to the `self` key
+    /// into `n` fragments for `n` proxies, with a threshold of `m`.
+    ///
+    /// Each proxy re-signs with its fragment via `ResigningKey::resign`, and a
+    /// threshold of the resulting re-signature fragments assemble into Alice's
+    /// signature, while fewer than `m` proxies can't re-sign anything.
+    fn split_resigning_key(&self, bob_pubkey: &PublicKey, m: usize, n: usize) -> Vec<ResigningKey> {
+        self.resigning_key(bob_pubkey).split(m, n)
+    }
+
+    /// Generates the `self` key's share of a bidirectional re-signing key
+    /// between the `self` key and the `counterparty_pubkey`.
+    ///
+    /// Both parties generate a share with each other's `PublicKey`, and the
+    /// proxy combines them with `BidirectionalResigningKey::new`. Neither party
+    /// needs the other's private key, as both shares re-sign from the same
+    /// designated key, which Alice and Bob derive alike.
+    ///
+    /// That designated key is derived under its own domain separation tag, so
+    /// a share is no unidirectional re-signing key: it doesn't re-sign
+    /// signatures under the counterparty's Penumbral designated key.
+    fn bidirectional_resigning_share(&self, counterparty_pubkey: &PublicKey) -> ResigningKey {
+        self.resigning_key_with_kdf(
+            counterparty_pubkey,
+            DesignatedKeyKdf::HkdfSha256(BIDIRECTIONAL_KEY_DST),
+        )
+    }
 
     /// Returns the designated PRS key that Bob signs with.
     /// Signatures made under this key can be re-signed from Bob to Alice given
     /// a re-signing key generated by Alice.
-    fn designated_key(&self, alice_pubkey: &PublicKey) -> Self;
+    ///
+    /// This derives the key with the default `DesignatedKeyKdf`, `Legacy`, as
+    /// earlier versions of NuBLS did.
+    fn designated_key(&self, alice_pubkey: &PublicKey) -> DesignatedKey {
+        self.designated_key_with_kdf(alice_pubkey, DesignatedKeyKdf::default())
+    }
+
+    /// Returns the designated PRS key that Bob signs with, derived with the
+    /// `kdf`. Alice must generate the re-signing key with the same `kdf`.
+    fn designated_key_with_kdf(
+        &self,
+        alice_pubkey: &PublicKey,
+        kdf: DesignatedKeyKdf,
+    ) -> DesignatedKey;
+
+    /// Returns the designated PRS key that Bob signs with in the `epoch`,
+    /// whose signatures can only be re-signed with a re-signing key for the
+    /// same `epoch` and `kdf`.
+    ///
+    /// Returns `Error::UnsupportedEpoch` if the `kdf` is `Legacy`.
+    fn designated_key_for_epoch(
+        &self,
+        alice_pubkey: &PublicKey,
+        epoch: &[u8],
+        kdf: DesignatedKeyKdf,
+    ) -> Result<DesignatedKey, Error>;
 
-    /// Performs a re-signature with the re-signing key to transform a
-    /// Signature under Bob's designated key to Alice's key.
-    fn resign(&self, signature: &Signature) -> Signature;
+    /// Returns the public key of Bob's designated key for Alice, which verifies
+    /// signatures under it. Bob computes it with Alice's `PublicKey`, and Alice
+    /// with Bob's.
+    fn designated_public_key(&self, counterparty_pubkey: &PublicKey) -> PublicKey;
 }
@@ -1,4 +1,8 @@
-use bls12_381::Scalar;
+use bls12_381::{G1Affine, Scalar};
+use std::collections::BTreeSet;
+
+use crate::error::Error;
+use crate::keys::PrivateKey;
 
 /// Evaluates a polynomial using Horner's method.
 ///
@@ -24,37 +28,258 @@ pub(crate) fn poly_eval(poly_coeffs: &[Scalar], x: &Scalar) -> Scalar {
     result
 }
 
+/// Returns the Lagrange basis coefficient of `fragment_index` for
+/// interpolating at zero from the points at `fragment_indices`, i.e. for
+/// recovering a secret from its fragments.
+///
+/// The `fragment_indices` must be distinct and non-zero, as checked by
+/// `fragment_indices`.
 pub(crate) fn lambda_coeff(fragment_index: &Scalar, fragment_indices: &[Scalar]) -> Scalar {
-    // First, filter the indices to remove the index we're evaluating
-    let indices = fragment_indices
-        .iter()
-        .filter(|&index| index != fragment_index)
-        .collect::<Vec<_>>();
+    basis_coeff(fragment_index, fragment_indices, &Scalar::zero())
+}
 
-    // Next, calculate the lambda coefficient with the remaining indices
-    // Note: divisions are performed by multiplying by the multiplicative
-    // inverse of the divisor.
-    let mut result: Scalar;
-    if let Some((&x_0, xs)) = indices.split_first() {
-        result = x_0 * (x_0 - fragment_index).invert().unwrap();
-        for &x_m in xs.into_iter() {
-            result *= x_m * (x_m - fragment_index).invert().unwrap();
+/// Returns the Lagrange basis coefficient of `fragment_index` for evaluating
+/// at `x` the polynomial through the points at `fragment_indices`.
+///
+/// Divisions are performed by multiplying by the multiplicative inverse of
+/// the divisor, so this takes a single inversion regardless of the number of
+/// `fragment_indices`, which must be distinct.
+pub(crate) fn basis_coeff(
+    fragment_index: &Scalar,
+    fragment_indices: &[Scalar],
+    x: &Scalar,
+) -> Scalar {
+    let mut numerator = Scalar::one();
+    let mut denominator = Scalar::one();
+    for x_l in fragment_indices.iter().filter(|&x_l| x_l != fragment_index) {
+        numerator *= x - x_l;
+        denominator *= fragment_index - x_l;
+    }
+    numerator * denominator.invert().unwrap()
+}
+
+/// Collects the fragment indices of a set of fragments for interpolation.
+///
+/// Returns `Error::NotEnoughFragments` if there are none, and
+/// `Error::InvalidFragmentIndex` or `Error::DuplicateFragmentIndex` if one
+/// isn't a fragment, has a zero index, or has the same index as another.
+pub(crate) fn fragment_indices<I>(indices: I) -> Result<Vec<Scalar>, Error>
+where
+    I: IntoIterator<Item = Option<Scalar>>,
+{
+    let mut seen = BTreeSet::<[u8; 32]>::new();
+    let mut fragment_indices = Vec::<Scalar>::new();
+    for index in indices {
+        match index {
+            Some(index) if index != Scalar::zero() => {
+                if !seen.insert(index.to_bytes()) {
+                    return Err(Error::DuplicateFragmentIndex);
+                }
+                fragment_indices.push(index);
+            }
+            _ => return Err(Error::InvalidFragmentIndex),
         }
+    }
+    if fragment_indices.is_empty() {
+        return Err(Error::NotEnoughFragments);
+    }
+    Ok(fragment_indices)
+}
+
+/// Evaluates at `x` the unique polynomial of degree `points.len() - 1` that
+/// passes through the `(index, value)` `points`, using Lagrange interpolation.
+///
+/// The indices of the `points` must be distinct.
+pub(crate) fn interpolate(points: &[(Scalar, Scalar)], x: &Scalar) -> Scalar {
+    let indices = points.iter().map(|(x_k, _)| *x_k).collect::<Vec<_>>();
+    points.iter().fold(Scalar::zero(), |acc, (x_k, y_k)| {
+        acc + basis_coeff(x_k, &indices[..], x) * y_k
+    })
+}
+
+/// Generates a random polynomial of degree `m-1` whose constant term is `secret`.
+///
+/// Evaluating this polynomial at `n` distinct, non-zero points yields the `n`
+/// fragments of an `m`-of-`n` Shamir's Secret Sharing of the `secret`.
+pub(crate) fn random_poly(secret: &Scalar, m: usize) -> Vec<Scalar> {
+    let mut coeffs = Vec::<Scalar>::with_capacity(m);
+    coeffs.push(*secret);
+    for _ in 1..m {
+        coeffs.push(PrivateKey::random().0);
+    }
+    coeffs
+}
+
+/// Deserializes a canonical `Scalar` from its 32 byte little-endian encoding.
+pub(crate) fn scalar_from_bytes(bytes: &[u8]) -> Result<Scalar, Error> {
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes.copy_from_slice(bytes);
+    Option::from(Scalar::from_bytes(&scalar_bytes)).ok_or(Error::NonCanonicalScalar)
+}
+
+/// The length of a fragment index serialized as a small fragment ID.
+pub(crate) const FRAGMENT_ID_BYTES_LENGTH: usize = 4;
+
+/// Returns the fragment index of the fragment with the fragment ID `id`.
+pub(crate) fn fragment_index(id: u32) -> Scalar {
+    Scalar::from(id as u64)
+}
+
+/// Returns the fragment ID of `fragment_index`, if it is small enough to fit
+/// in a `u32`.
+pub(crate) fn fragment_id(fragment_index: &Scalar) -> Option<u32> {
+    let bytes = fragment_index.to_bytes();
+    if bytes[FRAGMENT_ID_BYTES_LENGTH..]
+        .iter()
+        .any(|&byte| byte != 0)
+    {
+        return None;
+    }
+    let mut id_bytes = [0u8; FRAGMENT_ID_BYTES_LENGTH];
+    id_bytes.copy_from_slice(&bytes[..FRAGMENT_ID_BYTES_LENGTH]);
+    Some(u32::from_le_bytes(id_bytes))
+}
+
+/// Returns the length of `fragment_index` once serialized.
+///
+/// Fragment indices that fit in a `u32` are serialized compactly as a 4 byte
+/// big-endian fragment ID; others (e.g. the random indices of older
+/// fragments) as a 32 byte `Scalar`.
+pub(crate) fn fragment_index_length(fragment_index: &Scalar) -> usize {
+    match fragment_id(fragment_index) {
+        Some(_) => FRAGMENT_ID_BYTES_LENGTH,
+        None => 32,
+    }
+}
+
+/// Serializes `fragment_index` to a buffer of `fragment_index_length` bytes.
+pub(crate) fn fragment_index_to_bytes(fragment_index: &Scalar, buff: &mut [u8]) {
+    match fragment_id(fragment_index) {
+        Some(id) => buff.copy_from_slice(&id.to_be_bytes()),
+        None => buff.copy_from_slice(&fragment_index.to_bytes()),
+    }
+}
+
+/// Deserializes a fragment index from either a 4 byte fragment ID or a 32
+/// byte `Scalar`. The index must be non-zero and canonical.
+pub(crate) fn fragment_index_from_bytes(bytes: &[u8]) -> Result<Scalar, Error> {
+    let fragment_index = if bytes.len() == FRAGMENT_ID_BYTES_LENGTH {
+        let mut id_bytes = [0u8; FRAGMENT_ID_BYTES_LENGTH];
+        id_bytes.copy_from_slice(bytes);
+        fragment_index(u32::from_be_bytes(id_bytes))
     } else {
-        // Returns `one` when the `fragment_indices` slice is empty.
-        return Scalar::one();
+        scalar_from_bytes(bytes).map_err(|_| Error::InvalidFragmentIndex)?
+    };
+    if fragment_index == Scalar::zero() {
+        return Err(Error::InvalidFragmentIndex);
     }
-    result
+    Ok(fragment_index)
+}
+
+/// Deserializes a compressed point in G_1. Unlike a `PublicKey`, this
+/// accepts the identity.
+pub(crate) fn g1_from_bytes(bytes: &[u8]) -> Result<G1Affine, Error> {
+    if bytes.len() != 48 {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+    let mut point_bytes = [0u8; 48];
+    point_bytes.copy_from_slice(bytes);
+
+    let point: G1Affine = Option::from(G1Affine::from_compressed_unchecked(&point_bytes))
+        .ok_or(Error::PointNotOnCurve)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(Error::PointNotInSubgroup);
+    }
+    Ok(point)
+}
+
+/// Decodes a hex string into bytes, for the test vectors of the tests.
+#[cfg(test)]
+pub(crate) fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_interpolation() {
+        let coeffs = random_poly(&PrivateKey::random().0, 3);
+        let points = (1..4u64)
+            .map(|i| (Scalar::from(i), poly_eval(&coeffs[..], &Scalar::from(i))))
+            .collect::<Vec<_>>();
+
+        assert_eq!(interpolate(&points[..], &Scalar::zero()), coeffs[0]);
+        let x = Scalar::from(10);
+        assert_eq!(interpolate(&points[..], &x), poly_eval(&coeffs[..], &x));
+
+        let indices = points.iter().map(|(x_k, _)| *x_k).collect::<Vec<_>>();
+        for index in indices.iter() {
+            assert_eq!(
+                basis_coeff(index, &indices[..], &Scalar::zero()),
+                lambda_coeff(index, &indices[..])
+            );
+        }
+    }
+
+    #[test]
+    fn test_fragment_index_serialization() {
+        let small = fragment_index(0x0102_0304);
+        assert_eq!(fragment_id(&small), Some(0x0102_0304));
+        assert_eq!(fragment_index_length(&small), FRAGMENT_ID_BYTES_LENGTH);
+        let mut small_bytes = [0u8; FRAGMENT_ID_BYTES_LENGTH];
+        fragment_index_to_bytes(&small, &mut small_bytes);
+        assert_eq!(small_bytes, [1, 2, 3, 4]);
+        assert_eq!(fragment_index_from_bytes(&small_bytes), Ok(small));
+
+        let large = PrivateKey::random().0;
+        assert_eq!(fragment_id(&large), None);
+        assert_eq!(fragment_index_length(&large), 32);
+        let mut large_bytes = [0u8; 32];
+        fragment_index_to_bytes(&large, &mut large_bytes);
+        assert_eq!(fragment_index_from_bytes(&large_bytes), Ok(large));
+
+        assert_eq!(
+            fragment_index_from_bytes(&[0u8; FRAGMENT_ID_BYTES_LENGTH]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(
+            fragment_index_from_bytes(&[0u8; 32]),
+            Err(Error::InvalidFragmentIndex)
+        );
+    }
+
+    #[test]
+    fn test_fragment_indices() {
+        let one = Scalar::one();
+        let two = one.double();
+        assert_eq!(
+            fragment_indices(vec![Some(one), Some(two)]),
+            Ok(vec![one, two])
+        );
+        assert_eq!(
+            fragment_indices(vec![Some(one), Some(two), Some(one)]),
+            Err(Error::DuplicateFragmentIndex)
+        );
+        assert_eq!(
+            fragment_indices(vec![Some(one), Some(Scalar::zero())]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(
+            fragment_indices(vec![Some(one), None]),
+            Err(Error::InvalidFragmentIndex)
+        );
+        assert_eq!(fragment_indices(vec![]), Err(Error::NotEnoughFragments));
+    }
+
     #[test]
     fn test_polynomial_evaluation() {
         // [1, 2, 4]
-        let coeffs = vec![
+        let coeffs = [
             Scalar::one(),
             Scalar::one().double(),
             Scalar::one().double().double(),
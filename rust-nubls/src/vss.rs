@@ -0,0 +1,349 @@
+use bls12_381::{G1Affine, G1Projective, Scalar};
+
+use crate::bls::VerificationResult;
+use crate::error::Error;
+use crate::hash_to_curve::hash_to_g1;
+use crate::keys::{PublicKey, G1_POINT_BYTES_LENGTH, SCALAR_BYTES_LENGTH};
+use crate::utils::{g1_from_bytes, scalar_from_bytes};
+
+/// The domain separation tag used to derive the Pedersen generator `H`.
+const PEDERSEN_GENERATOR_DST: &[u8] = b"NUBLS-PEDERSEN-VSS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// `FeldmanCommitments` are the public commitments to the coefficients of the
+/// polynomial used to split a `PrivateKey` with Feldman's Verifiable Secret
+/// Sharing (VSS).
+///
+/// For a sharing polynomial $f(x) = a_0 + a_1x + ... + a_{m-1}x^{m-1}$, the
+/// commitments are $C_j = a_j \cdot G_1$. A fragment $(i, f(i))$ is consistent
+/// with the polynomial if and only if:
+/// $f(i) \cdot G_1 = \sum_j i^j \cdot C_j$
+///
+/// The first commitment $C_0$ is the `PublicKey` of the split key.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FeldmanCommitments(pub(crate) Vec<G1Affine>);
+
+impl FeldmanCommitments {
+    /// Commits to each of the polynomial coefficients `coeffs`.
+    pub(crate) fn new(coeffs: &[Scalar]) -> FeldmanCommitments {
+        FeldmanCommitments(
+            coeffs
+                .iter()
+                .map(|coeff| G1Affine::from(G1Affine::generator() * coeff))
+                .collect(),
+        )
+    }
+
+    /// Returns the threshold `m` of the split, which is the number of
+    /// committed coefficients.
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the `PublicKey` of the key that was split.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0[0], None)
+    }
+
+    /// Returns the `PublicKey` of the fragment with `fragment_index`, which
+    /// verifies its signature fragments with `PublicKey::verify_fragment`.
+    pub fn fragment_public_key(&self, fragment_index: &Scalar) -> PublicKey {
+        PublicKey(self.evaluate(fragment_index).into(), Some(*fragment_index))
+    }
+
+    /// Evaluates the committed polynomial "in the exponent" at `x`,
+    /// returning $f(x) \cdot G_1$.
+    pub(crate) fn evaluate(&self, x: &Scalar) -> G1Projective {
+        evaluate_commitments(&self.0[..], x)
+    }
+
+    /// Verifies that `value` is the evaluation of the committed polynomial at
+    /// `fragment_index`.
+    pub(crate) fn verify(&self, value: &Scalar, fragment_index: &Scalar) -> VerificationResult {
+        if self.0.is_empty() || fragment_index == &Scalar::zero() {
+            return VerificationResult::Invalid;
+        }
+        VerificationResult::from(
+            G1Affine::from(G1Affine::generator() * value)
+                == G1Affine::from(self.evaluate(fragment_index)),
+        )
+    }
+
+    /// Serializes the `FeldmanCommitments` as the concatenation of the 48 byte
+    /// compressed commitments.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        commitments_to_bytes(&self.0[..])
+    }
+
+    /// Deserializes `FeldmanCommitments` from the concatenation of 48 byte
+    /// compressed commitments.
+    /// This returns an `Error` if the input is empty, is not a multiple of 48
+    /// bytes long, or contains a point that is not in G_1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<FeldmanCommitments, Error> {
+        Ok(FeldmanCommitments(commitments_from_bytes(bytes)?))
+    }
+}
+
+/// `PedersenCommitments` are hiding commitments to the coefficients of the
+/// polynomial used to split a `PrivateKey` with Pedersen's Verifiable Secret
+/// Sharing (VSS).
+///
+/// Unlike `FeldmanCommitments`, these don't reveal the `PublicKey` of the
+/// split key. Each coefficient $a_j$ of the sharing polynomial $f$ is blinded
+/// by the coefficient $b_j$ of a random blinding polynomial $g$:
+/// $C_j = a_j \cdot G_1 + b_j \cdot H$
+/// where $H$ is a second generator of G_1 derived with `hash_to_g1`, so no
+/// one knows its discrete log with respect to $G_1$. A fragment $(i, f(i))$
+/// with its `BlindingShare` $g(i)$ is consistent with the polynomial if and
+/// only if:
+/// $f(i) \cdot G_1 + g(i) \cdot H = \sum_j i^j \cdot C_j$
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PedersenCommitments(pub(crate) Vec<G1Affine>);
+
+/// A `BlindingShare` is the evaluation of the blinding polynomial of a
+/// Pedersen VSS at a fragment's index. It is handed to the holder of that
+/// fragment, and is only needed to verify the fragment.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct BlindingShare(pub(crate) Scalar);
+
+impl PedersenCommitments {
+    /// Commits to each of the polynomial coefficients `coeffs`, blinded by
+    /// the `blinding_coeffs`.
+    pub(crate) fn new(coeffs: &[Scalar], blinding_coeffs: &[Scalar]) -> PedersenCommitments {
+        let generator = pedersen_generator();
+        PedersenCommitments(
+            coeffs
+                .iter()
+                .zip(blinding_coeffs.iter())
+                .map(|(coeff, blinding_coeff)| {
+                    G1Affine::from(G1Affine::generator() * coeff + generator * blinding_coeff)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the threshold `m` of the split, which is the number of
+    /// committed coefficients.
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Verifies that `value` and `blinding_share` are the evaluations of the
+    /// committed polynomials at `fragment_index`.
+    pub(crate) fn verify(
+        &self,
+        value: &Scalar,
+        blinding_share: &BlindingShare,
+        fragment_index: &Scalar,
+    ) -> VerificationResult {
+        if self.0.is_empty() || fragment_index == &Scalar::zero() {
+            return VerificationResult::Invalid;
+        }
+        let expected = G1Affine::generator() * value + pedersen_generator() * blinding_share.0;
+        VerificationResult::from(
+            G1Affine::from(expected)
+                == G1Affine::from(evaluate_commitments(&self.0[..], fragment_index)),
+        )
+    }
+
+    /// Serializes the `PedersenCommitments` as the concatenation of the 48
+    /// byte compressed commitments.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        commitments_to_bytes(&self.0[..])
+    }
+
+    /// Deserializes `PedersenCommitments` from the concatenation of 48 byte
+    /// compressed commitments.
+    /// This returns an `Error` if the input is empty, is not a multiple of 48
+    /// bytes long, or contains a point that is not in G_1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PedersenCommitments, Error> {
+        Ok(PedersenCommitments(commitments_from_bytes(bytes)?))
+    }
+}
+
+impl BlindingShare {
+    /// Serializes the `BlindingShare` to an array of 32 bytes.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Deserializes from a `&[u8]` to a `BlindingShare`.
+    /// This returns an `Error` if the input is not 32 bytes long, or is not
+    /// canonical.
+    pub fn from_bytes(bytes: &[u8]) -> Result<BlindingShare, Error> {
+        if bytes.len() != SCALAR_BYTES_LENGTH {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(BlindingShare(scalar_from_bytes(bytes)?))
+    }
+}
+
+/// Returns the second generator `H` of G_1 used by Pedersen commitments.
+pub(crate) fn pedersen_generator() -> G1Affine {
+    hash_to_g1(b"H", PEDERSEN_GENERATOR_DST)
+}
+
+/// Evaluates a polynomial "in the exponent" given the `commitments` to its
+/// coefficients with Horner's method.
+fn evaluate_commitments(commitments: &[G1Affine], x: &Scalar) -> G1Projective {
+    commitments
+        .iter()
+        .rev()
+        .fold(G1Projective::identity(), |acc, commitment| {
+            acc * x + commitment
+        })
+}
+
+fn commitments_to_bytes(commitments: &[G1Affine]) -> Vec<u8> {
+    commitments
+        .iter()
+        .flat_map(|commitment| commitment.to_compressed().to_vec())
+        .collect()
+}
+
+fn commitments_from_bytes(bytes: &[u8]) -> Result<Vec<G1Affine>, Error> {
+    if bytes.is_empty() || bytes.len() % G1_POINT_BYTES_LENGTH != 0 {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+    bytes
+        .chunks(G1_POINT_BYTES_LENGTH)
+        .map(g1_from_bytes)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::PrivateKey;
+    use crate::traits::ThresholdKey;
+
+    #[test]
+    fn test_feldman_fragments_verify() {
+        let priv_a = PrivateKey::random();
+        let (fragments, commitments) = priv_a.split_verifiable(3, 5);
+
+        assert_eq!(commitments.threshold(), 3);
+        assert_eq!(commitments.public_key(), priv_a.public_key());
+        for fragment in fragments.iter() {
+            assert_eq!(
+                fragment.verify_fragment(&commitments),
+                VerificationResult::Valid
+            );
+        }
+
+        // The fragments still recover the key like those from `split`.
+        assert_eq!(PrivateKey::recover(&fragments[1..4]).unwrap(), priv_a);
+    }
+
+    #[test]
+    fn test_cheating_dealer_is_detected() {
+        let priv_a = PrivateKey::random();
+        let (fragments, commitments) = priv_a.split_verifiable(3, 5);
+
+        // A fragment that is not on the committed polynomial.
+        let bad_fragment = PrivateKey(fragments[0].0 + Scalar::one(), fragments[0].1);
+        assert_eq!(
+            bad_fragment.verify_fragment(&commitments),
+            VerificationResult::Invalid
+        );
+
+        // Fragments from a different split don't verify.
+        let (_, other_commitments) = priv_a.split_verifiable(3, 5);
+        assert_eq!(
+            fragments[0].verify_fragment(&other_commitments),
+            VerificationResult::Invalid
+        );
+
+        // A key that isn't a fragment doesn't verify.
+        assert_eq!(
+            priv_a.verify_fragment(&commitments),
+            VerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_pedersen_fragments_verify() {
+        let priv_a = PrivateKey::random();
+        let (fragments, blinding_shares, commitments) = priv_a.split_pedersen(3, 5);
+
+        assert_eq!(commitments.threshold(), 3);
+        for (fragment, blinding_share) in fragments.iter().zip(blinding_shares.iter()) {
+            assert_eq!(
+                fragment.verify_pedersen_fragment(blinding_share, &commitments),
+                VerificationResult::Valid
+            );
+        }
+
+        // The commitments hide the public key, unlike Feldman commitments.
+        let feldman = FeldmanCommitments(commitments.0.clone());
+        assert_ne!(feldman.public_key(), priv_a.public_key());
+
+        assert_eq!(PrivateKey::recover(&fragments[2..5]).unwrap(), priv_a);
+    }
+
+    #[test]
+    fn test_pedersen_cheating_dealer_is_detected() {
+        let priv_a = PrivateKey::random();
+        let (fragments, blinding_shares, commitments) = priv_a.split_pedersen(3, 5);
+
+        let bad_fragment = PrivateKey(fragments[0].0 + Scalar::one(), fragments[0].1);
+        assert_eq!(
+            bad_fragment.verify_pedersen_fragment(&blinding_shares[0], &commitments),
+            VerificationResult::Invalid
+        );
+
+        // A blinding share for another fragment doesn't verify.
+        assert_eq!(
+            fragments[0].verify_pedersen_fragment(&blinding_shares[1], &commitments),
+            VerificationResult::Invalid
+        );
+
+        assert_eq!(
+            priv_a.verify_pedersen_fragment(&blinding_shares[0], &commitments),
+            VerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_pedersen_generator() {
+        let generator = pedersen_generator();
+        assert!(bool::from(generator.is_torsion_free()));
+        assert!(!bool::from(generator.is_identity()));
+        assert_ne!(generator, G1Affine::generator());
+    }
+
+    #[test]
+    fn test_commitment_serialization() {
+        let priv_a = PrivateKey::random();
+        let (_, commitments) = priv_a.split_verifiable(3, 5);
+
+        let commitment_bytes = commitments.to_bytes();
+        assert_eq!(commitment_bytes.len(), 3 * 48);
+        assert_eq!(
+            FeldmanCommitments::from_bytes(&commitment_bytes),
+            Ok(commitments)
+        );
+
+        assert_eq!(
+            FeldmanCommitments::from_bytes(&[]),
+            Err(Error::InvalidLength(0))
+        );
+        assert_eq!(
+            FeldmanCommitments::from_bytes(&commitment_bytes[1..]),
+            Err(Error::InvalidLength(3 * 48 - 1))
+        );
+
+        let (_, blinding_shares, commitments) = priv_a.split_pedersen(3, 5);
+        assert_eq!(
+            PedersenCommitments::from_bytes(&commitments.to_bytes()),
+            Ok(commitments)
+        );
+        assert_eq!(
+            BlindingShare::from_bytes(&blinding_shares[0].to_bytes()),
+            Ok(blinding_shares[0])
+        );
+        assert_eq!(
+            BlindingShare::from_bytes(&[0xffu8; 32]),
+            Err(Error::NonCanonicalScalar)
+        );
+    }
+}